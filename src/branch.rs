@@ -0,0 +1,191 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::Command;
+
+/// Default branch pattern: `<type>/<TICKET>-<description>`, e.g. `feat/PROJ-123-login`
+pub const DEFAULT_BRANCH_PATTERN: &str =
+    r"^(?P<type>[a-z]+)/(?P<ticket>[A-Z][A-Z0-9_]+-\d+)(?:[-_/].*)?$";
+
+/// How the branch-name rule is applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BranchMode {
+    /// Don't look at the branch
+    #[default]
+    Off,
+    /// Print hints when the commit disagrees with the branch
+    Suggest,
+    /// Reject commits that disagree with the branch
+    Enforce,
+}
+
+/// Branch-name rule settings, configured under `[branch]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BranchConfig {
+    /// "off", "suggest" or "enforce"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<BranchMode>,
+
+    /// Branch regex with optional named groups `type` and `ticket`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Branch prefixes mapped to commit types, e.g. `feature = "feat"`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub type_map: BTreeMap<String, String>,
+}
+
+impl BranchConfig {
+    /// The built-in defaults, with every option set
+    pub fn defaults() -> Self {
+        Self {
+            mode: Some(BranchMode::Off),
+            pattern: Some(DEFAULT_BRANCH_PATTERN.to_string()),
+            type_map: [("feature", "feat"), ("bugfix", "fix"), ("hotfix", "fix")]
+                .into_iter()
+                .map(|(from, to)| (from.to_string(), to.to_string()))
+                .collect(),
+        }
+    }
+
+    /// Compile the configured pattern
+    pub fn rules(&self) -> Result<BranchRules> {
+        let source = self.pattern.as_deref().unwrap_or(DEFAULT_BRANCH_PATTERN);
+        let pattern =
+            Regex::new(source).with_context(|| format!("invalid branch.pattern `{source}`"))?;
+        let groups: Vec<&str> = pattern.capture_names().flatten().collect();
+        if !groups.contains(&"type") && !groups.contains(&"ticket") {
+            bail!("branch.pattern `{source}` has neither a `type` nor a `ticket` named group");
+        }
+
+        Ok(BranchRules {
+            mode: self.mode.unwrap_or_default(),
+            pattern,
+            type_map: self.type_map.clone(),
+        })
+    }
+}
+
+/// Compiled branch-name rule
+#[derive(Debug, Clone)]
+pub struct BranchRules {
+    pub mode: BranchMode,
+    pub pattern: Regex,
+    pub type_map: BTreeMap<String, String>,
+}
+
+/// What a branch name says a commit should look like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchExpectation {
+    /// Commit type implied by the branch, after `type_map`
+    pub commit_type: Option<String>,
+    /// Ticket key the commit should reference
+    pub ticket: Option<String>,
+}
+
+impl BranchRules {
+    /// Expectations derived from `branch`, or `None` if it doesn't match the pattern
+    pub fn expectation(&self, branch: &str) -> Option<BranchExpectation> {
+        let captures = self.pattern.captures(branch)?;
+        let commit_type = captures.name("type").map(|m| {
+            let prefix = m.as_str();
+            self.type_map
+                .get(prefix)
+                .cloned()
+                .unwrap_or_else(|| prefix.to_string())
+        });
+        let ticket = captures.name("ticket").map(|m| m.as_str().to_string());
+        Some(BranchExpectation {
+            commit_type,
+            ticket,
+        })
+    }
+}
+
+/// Whether `message` mentions `ticket` as a whole word, so `PROJ-12` is not satisfied
+/// by `PROJ-123`, `XPROJ-12` or a URL path ending in `/PROJ-12`
+pub fn mentions_ticket(message: &str, ticket: &str) -> bool {
+    let joins = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '/');
+    !ticket.is_empty()
+        && message.match_indices(ticket).any(|(start, _)| {
+            let before = message[..start].chars().next_back();
+            let after = message[start + ticket.len()..].chars().next();
+            !before.is_some_and(joins) && !after.is_some_and(joins)
+        })
+}
+
+/// Name of the branch checked out in `dir`, or `None` for a detached HEAD
+pub fn current_branch(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["symbolic-ref", "--short", "-q", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8(output.stdout).ok()?;
+    let branch = branch.trim();
+    (!branch.is_empty()).then(|| branch.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_pattern_reads_type_and_ticket() {
+        let rules = BranchConfig::defaults().rules().unwrap();
+        assert_eq!(
+            rules.expectation("feature/PROJ-123-short-desc"),
+            Some(BranchExpectation {
+                commit_type: Some("feat".to_string()),
+                ticket: Some("PROJ-123".to_string()),
+            })
+        );
+        assert_eq!(
+            rules.expectation("fix/PROJ-9").and_then(|e| e.commit_type),
+            Some("fix".to_string())
+        );
+        assert_eq!(rules.expectation("main"), None);
+    }
+
+    #[test]
+    fn ticket_must_be_mentioned_as_a_word() {
+        assert!(mentions_ticket("feat: x\n\nRefs: PROJ-12\n", "PROJ-12"));
+        assert!(mentions_ticket("feat: x (PROJ-12)", "PROJ-12"));
+        assert!(mentions_ticket("fix: typo\n\nCloses #42", "42"));
+        assert!(!mentions_ticket("feat: x\n\nRefs: PROJ-123\n", "PROJ-12"));
+        assert!(!mentions_ticket("feat: x for XPROJ-12", "PROJ-12"));
+        assert!(!mentions_ticket(
+            "feat: x\n\nSee https://jira.example.com/browse/PROJ-12\n",
+            "PROJ-12"
+        ));
+    }
+
+    #[test]
+    fn custom_pattern_needs_a_known_group() {
+        let config = BranchConfig {
+            pattern: Some(r"^(?P<ticket>\d+)-.*$".to_string()),
+            ..BranchConfig::defaults()
+        };
+        let rules = config.rules().unwrap();
+        assert_eq!(
+            rules.expectation("42-typo"),
+            Some(BranchExpectation {
+                commit_type: None,
+                ticket: Some("42".to_string()),
+            })
+        );
+
+        let config = BranchConfig {
+            pattern: Some(r"^.*$".to_string()),
+            ..BranchConfig::defaults()
+        };
+        assert!(config.rules().is_err());
+    }
+}
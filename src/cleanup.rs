@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+/// How a commit message is cleaned up before validation, mirroring git's
+/// `commit.cleanup` / `git commit --cleanup=<mode>`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupMode {
+    /// Use the repository's `commit.cleanup` setting, falling back to `strip`
+    #[default]
+    Default,
+    /// Strip comment lines, trailing whitespace and surplus blank lines
+    Strip,
+    /// Strip trailing whitespace and surplus blank lines but keep comment lines
+    Whitespace,
+    /// Leave the message untouched
+    Verbatim,
+    /// Like `whitespace`, and also drop everything from the scissors line down
+    Scissors,
+}
+
+impl std::str::FromStr for CleanupMode {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "default" => Ok(CleanupMode::Default),
+            "strip" => Ok(CleanupMode::Strip),
+            "whitespace" => Ok(CleanupMode::Whitespace),
+            "verbatim" => Ok(CleanupMode::Verbatim),
+            "scissors" => Ok(CleanupMode::Scissors),
+            _ => Err("supported modes: default, strip, whitespace, verbatim, scissors".to_string()),
+        }
+    }
+}
+
+/// Comment prefix git uses unless `core.commentChar` says otherwise
+pub const DEFAULT_COMMENT: &str = "#";
+
+/// Candidates git tries, in order, for `core.commentChar = auto`
+const AUTO_COMMENT_CHARS: &str = "#;@!$%^&|:";
+
+/// The scissors line git writes above the diff of `git commit --verbose`
+pub fn scissors_line(comment: &str) -> String {
+    format!("{comment} ------------------------ >8 ------------------------")
+}
+
+/// Work out the comment character of a message written with `core.commentChar = auto`
+///
+/// git picks the first candidate no message line starts with before appending its own
+/// comments, so the prefix of those comments (the scissors line or the usual "Please
+/// enter the commit message" help text) identifies it; otherwise `#` is assumed.
+pub fn detect_comment(message: &str) -> String {
+    for candidate in AUTO_COMMENT_CHARS.chars() {
+        let prefix = candidate.to_string();
+        let marks_git_comment = message.lines().any(|line| {
+            line == scissors_line(&prefix)
+                || line.starts_with(&format!("{prefix} Please enter the commit message"))
+        });
+        if marks_git_comment {
+            return prefix;
+        }
+    }
+    DEFAULT_COMMENT.to_string()
+}
+
+/// Clean up `message` the way git does before storing it; `comment` is the comment
+/// prefix (`core.commentChar`/`core.commentString`), or "auto" to detect it
+///
+/// The message is always cut at the scissors line: git only writes one for
+/// `--verbose` and `--cleanup=scissors`, and drops everything below it in both cases.
+pub fn cleanup(message: &str, mode: CleanupMode, comment: &str) -> String {
+    if mode == CleanupMode::Verbatim {
+        return message.to_string();
+    }
+
+    let comment = if comment == "auto" {
+        detect_comment(message)
+    } else {
+        comment.to_string()
+    };
+    let scissors = scissors_line(&comment);
+    let strip_comments = matches!(mode, CleanupMode::Strip | CleanupMode::Default);
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in message.lines() {
+        if line == scissors {
+            break;
+        }
+        // Unlike git, indented comment lines count too, as cc-check has always allowed
+        if strip_comments && line.trim_start().starts_with(comment.as_str()) {
+            continue;
+        }
+        let line = line.trim_end();
+        // Collapse runs of blank lines and drop leading ones
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+
+    if lines.is_empty() {
+        return String::new();
+    }
+    let mut cleaned = lines.join("\n");
+    cleaned.push('\n');
+    cleaned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERBOSE_MESSAGE: &str = "\n\nfeat: add x   \n\n\n\nbody\n# Please enter the commit message for your changes.\n# ------------------------ >8 ------------------------\n# Do not modify or remove the line above.\ndiff --git a/x b/x\n";
+
+    #[test]
+    fn strip_removes_comments_whitespace_and_diff() {
+        assert_eq!(
+            cleanup(VERBOSE_MESSAGE, CleanupMode::Strip, "#"),
+            "feat: add x\n\nbody\n"
+        );
+    }
+
+    #[test]
+    fn whitespace_and_scissors_keep_comments() {
+        let message = "feat: add x\n\n#123 is fixed\n";
+        assert_eq!(cleanup(message, CleanupMode::Whitespace, "#"), message);
+        assert_eq!(cleanup(message, CleanupMode::Strip, "#"), "feat: add x\n");
+        assert_eq!(
+            cleanup(VERBOSE_MESSAGE, CleanupMode::Scissors, "#"),
+            "feat: add x\n\nbody\n# Please enter the commit message for your changes.\n"
+        );
+    }
+
+    #[test]
+    fn verbatim_keeps_everything() {
+        assert_eq!(
+            cleanup(VERBOSE_MESSAGE, CleanupMode::Verbatim, "#"),
+            VERBOSE_MESSAGE
+        );
+    }
+
+    #[test]
+    fn custom_and_auto_comment_chars() {
+        let message = "feat: add x\n#123 stays\n; Please enter the commit message\n";
+        assert_eq!(
+            cleanup(message, CleanupMode::Strip, ";"),
+            "feat: add x\n#123 stays\n"
+        );
+        assert_eq!(detect_comment(message), ";");
+        assert_eq!(
+            cleanup(message, CleanupMode::Strip, "auto"),
+            "feat: add x\n#123 stays\n"
+        );
+        assert_eq!(detect_comment("feat: add x\n"), "#");
+    }
+}
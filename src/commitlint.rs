@@ -0,0 +1,590 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::content::Severity;
+use crate::custom_rules::{CustomRuleConfig, Target};
+
+/// commitlint config files cc-check can read, in commitlint's order of preference
+pub const FILE_NAMES: &[&str] = &[
+    ".commitlintrc",
+    ".commitlintrc.json",
+    ".commitlintrc.yaml",
+    ".commitlintrc.yml",
+];
+
+/// JavaScript/TypeScript commitlint configs, which cc-check can't evaluate
+pub const SCRIPT_FILE_NAMES: &[&str] = &[
+    ".commitlintrc.js",
+    ".commitlintrc.cjs",
+    ".commitlintrc.mjs",
+    ".commitlintrc.ts",
+    "commitlint.config.js",
+    "commitlint.config.cjs",
+    "commitlint.config.mjs",
+    "commitlint.config.ts",
+];
+
+/// The shareable config most repositories extend
+const CONFIG_CONVENTIONAL: &str = "@commitlint/config-conventional";
+
+/// Rules of `@commitlint/config-conventional`
+const CONFIG_CONVENTIONAL_RULES: &str = r#"{
+    "body-leading-blank": [1, "always"],
+    "body-max-line-length": [2, "always", 100],
+    "footer-leading-blank": [1, "always"],
+    "footer-max-line-length": [2, "always", 100],
+    "header-max-length": [2, "always", 100],
+    "header-trim": [2, "always"],
+    "subject-case": [2, "never", ["sentence-case", "start-case", "pascal-case", "upper-case"]],
+    "subject-empty": [2, "never"],
+    "subject-full-stop": [2, "never", "."],
+    "type-case": [2, "always", "lower-case"],
+    "type-empty": [2, "never"],
+    "type-enum": [2, "always", ["build", "chore", "ci", "docs", "feat", "fix", "perf", "refactor", "revert", "style", "test"]]
+}"#;
+
+/// Rules cc-check always enforces, in the form commitlint configs usually give them
+const ALWAYS_ENFORCED: &[(&str, &str)] = &[
+    ("header-trim", "always"),
+    ("subject-empty", "never"),
+    ("type-case", "always"),
+    ("type-empty", "never"),
+];
+
+/// A commitlint config mapped onto cc-check's
+#[derive(Debug, Clone, Default)]
+pub struct Conversion {
+    pub config: Config,
+    /// What could not be carried over exactly
+    pub notes: Vec<String>,
+}
+
+/// Whether `path` is named like a commitlint config file
+pub fn is_config_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| FILE_NAMES.contains(&name))
+}
+
+/// The commitlint config in `dir`; a JavaScript one is an error, as it can't be read
+pub fn find_in_dir(dir: &Path) -> Result<Option<PathBuf>> {
+    if let Some(path) = FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+    {
+        return Ok(Some(path));
+    }
+    if let Some(script) = SCRIPT_FILE_NAMES
+        .iter()
+        .find(|name| dir.join(name).is_file())
+    {
+        bail!(
+            "{script} is JavaScript, which cc-check can't read; save the config as JSON \
+             (e.g. `npx commitlint --print-config json > .commitlintrc.json`)"
+        );
+    }
+    Ok(None)
+}
+
+/// Read a commitlint config file (JSON, or YAML for the other names) and convert it
+pub fn load(path: &Path) -> Result<Conversion> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read config file: {}", path.display()))?;
+    parse(&contents, path)
+}
+
+/// Parse and convert the `contents` of the commitlint config at `path`
+pub fn parse(contents: &str, path: &Path) -> Result<Conversion> {
+    let value: Value = if path.extension().is_some_and(|ext| ext == "json") {
+        serde_json::from_str(contents)
+            .with_context(|| format!("{} is not valid JSON", path.display()))?
+    } else {
+        // YAML is a superset of JSON, so this covers `.commitlintrc` in either syntax
+        serde_yaml::from_str(contents)
+            .with_context(|| format!("{} is not valid YAML", path.display()))?
+    };
+    convert(&value)
+}
+
+/// Map a parsed commitlint config onto cc-check's config
+pub fn convert(value: &Value) -> Result<Conversion> {
+    let Some(object) = value.as_object() else {
+        bail!("a commitlint config must be an object");
+    };
+    let mut conversion = Conversion {
+        config: Config {
+            // commitlint doesn't limit these unless a rule says so
+            max_subject: Some(0),
+            no_trailing_period: Some(false),
+            ..Config::default()
+        },
+        notes: Vec::new(),
+    };
+
+    let mut rules: BTreeMap<String, Value> = BTreeMap::new();
+    let extends = match object.get("extends") {
+        Some(Value::String(name)) => vec![name.clone()],
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(|name| name.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    };
+    for name in extends {
+        if name == CONFIG_CONVENTIONAL || name == "config-conventional" {
+            let base: BTreeMap<String, Value> =
+                serde_json::from_str(CONFIG_CONVENTIONAL_RULES).expect("valid built-in rules");
+            rules.extend(base);
+        } else {
+            conversion
+                .notes
+                .push(format!("extends `{name}` is not supported and was ignored"));
+        }
+    }
+    for key in ["parserPreset", "ignores", "plugins"] {
+        if object.contains_key(key) {
+            conversion
+                .notes
+                .push(format!("`{key}` is not supported and was ignored"));
+        }
+    }
+    if let Some(own) = object.get("rules").and_then(Value::as_object) {
+        rules.extend(own.iter().map(|(name, rule)| (name.clone(), rule.clone())));
+    }
+
+    for (name, rule) in &rules {
+        let Some(rule) = Rule::parse(rule) else {
+            conversion.notes.push(format!(
+                "rule `{name}` has an unexpected format and was ignored"
+            ));
+            continue;
+        };
+        if rule.level == 0 {
+            continue;
+        }
+        if !conversion.apply(name, &rule) {
+            conversion
+                .notes
+                .push(format!("rule `{name}` is not supported and was ignored"));
+        }
+    }
+    Ok(conversion)
+}
+
+/// A commitlint rule: `[level, "always" | "never", value]`
+struct Rule {
+    level: u64,
+    always: bool,
+    value: Option<Value>,
+}
+
+impl Rule {
+    fn parse(value: &Value) -> Option<Self> {
+        let items = value.as_array()?;
+        let level = items.first()?.as_u64().filter(|level| *level <= 2)?;
+        let always = match items.get(1).and_then(Value::as_str) {
+            None | Some("always") => true,
+            Some("never") => false,
+            Some(_) => return None,
+        };
+        Some(Self {
+            level,
+            always,
+            value: items.get(2).cloned(),
+        })
+    }
+
+    fn severity(&self) -> Severity {
+        if self.level == 1 {
+            Severity::Warning
+        } else {
+            Severity::Error
+        }
+    }
+
+    fn number(&self) -> Option<usize> {
+        self.value.as_ref()?.as_u64().map(|n| n as usize)
+    }
+
+    fn strings(&self) -> Vec<String> {
+        match &self.value {
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(|item| item.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl Conversion {
+    /// Carry `rule` over; false if cc-check has no equivalent
+    fn apply(&mut self, name: &str, rule: &Rule) -> bool {
+        let config = &mut self.config;
+        let native = match (name, rule.always) {
+            ("type-enum", true) => {
+                config.types = rule.strings();
+                true
+            }
+            ("subject-case", _) => {
+                let Some((custom, unchecked)) = subject_case_rule(rule) else {
+                    return false;
+                };
+                if !unchecked.is_empty() {
+                    self.notes.push(format!(
+                        "rule `subject-case` is only partly supported: {} subjects are not rejected",
+                        unchecked.join(", ")
+                    ));
+                }
+                config.custom_rules.push(custom);
+                return true;
+            }
+            ("subject-full-stop" | "header-full-stop", false) => {
+                config.no_trailing_period = Some(true);
+                true
+            }
+            ("subject-max-length", true) => match rule.number() {
+                Some(max) => {
+                    config.max_subject = Some(max);
+                    true
+                }
+                None => return false,
+            },
+            ("subject-min-length", true) => match rule.number() {
+                Some(min) => {
+                    config.min_subject = Some(min);
+                    true
+                }
+                None => return false,
+            },
+            ("header-max-length", true) => match rule.number() {
+                Some(max) => {
+                    config.max_header = Some(max);
+                    true
+                }
+                None => return false,
+            },
+            ("signed-off-by", true) => {
+                config.signoff.required = Some(true);
+                true
+            }
+            ("references-empty", false) => {
+                config.references.required_for = vec!["*".to_string()];
+                true
+            }
+            ("trailer-exists", true) => {
+                let strings = rule.strings();
+                let Some(token) = strings.first() else {
+                    return false;
+                };
+                let token = token.trim().trim_end_matches(':').to_string();
+                config.trailers.required.push(token);
+                true
+            }
+            _ if ALWAYS_ENFORCED
+                .iter()
+                .any(|&(enforced, form)| enforced == name && (form == "always") == rule.always) =>
+            {
+                return true;
+            }
+            _ => {
+                let Some(custom) = custom_rule(name, rule) else {
+                    return false;
+                };
+                self.config.custom_rules.push(custom);
+                return true;
+            }
+        };
+        if native && rule.level == 1 {
+            self.notes.push(format!(
+                "rule `{name}` is a warning in commitlint but an error in cc-check"
+            ));
+        }
+        native
+    }
+}
+
+/// `subject-case` as a custom rule, with the cases it can't check
+///
+/// Only the cases commitlint users actually restrict are supported, following
+/// commitlint's definitions: sentence-case looks at the first word only ("Add x", but
+/// not "API docs"), upper-case and lower-case at the whole subject.
+fn subject_case_rule(rule: &Rule) -> Option<(CustomRuleConfig, Vec<String>)> {
+    let cases = rule.strings();
+    let (pattern, forbid_pattern, message, unchecked) = if rule.always {
+        let (pattern, forbid_pattern) = match cases.as_slice() {
+            [case] if case == "lower-case" => (None, Some(r"\p{Lu}")),
+            [case] if case == "sentence-case" => (Some(r"^\p{Lu}[^\p{Lu}\s]*(?:\s|$)"), None),
+            [case] if case == "upper-case" => (None, Some(r"\p{Ll}")),
+            _ => return None,
+        };
+        (
+            pattern,
+            forbid_pattern.map(String::from),
+            format!("subject must be {}", cases[0]),
+            Vec::new(),
+        )
+    } else {
+        let mut checked = Vec::new();
+        let mut forbidden = Vec::new();
+        let mut unchecked = Vec::new();
+        for case in &cases {
+            let pattern = match case.as_str() {
+                "sentence-case" => r"^\p{Lu}[^\p{Lu}\s]*(?:\s|$)",
+                "upper-case" => r"^\P{Ll}*\p{Lu}\P{Ll}*$",
+                "lower-case" => r"^\P{Lu}*\p{Ll}\P{Lu}*$",
+                _ => {
+                    unchecked.push(case.clone());
+                    continue;
+                }
+            };
+            checked.push(case.as_str());
+            forbidden.push(pattern);
+        }
+        if forbidden.is_empty() {
+            return None;
+        }
+        (
+            None,
+            Some(format!("(?:{})", forbidden.join("|"))),
+            format!("subject must not be {}", checked.join(" or ")),
+            unchecked,
+        )
+    };
+    let custom = CustomRuleConfig {
+        id: "subject-case".to_string(),
+        target: Some(Target::Subject),
+        pattern: pattern.map(String::from),
+        forbid_pattern,
+        when: None,
+        severity: Some(rule.severity()),
+        message: Some(message),
+    };
+    Some((custom, unchecked))
+}
+
+/// Rules without a native option, expressed as a `[[custom_rules]]` entry
+fn custom_rule(name: &str, rule: &Rule) -> Option<CustomRuleConfig> {
+    let (target, pattern, forbid_pattern, message) = match (name, rule.always) {
+        ("scope-enum", true) => {
+            let scopes = rule.strings();
+            let alternatives: Vec<String> = scopes.iter().map(|s| regex::escape(s)).collect();
+            (
+                Target::Scope,
+                Some(format!("^(?:{})?$", alternatives.join("|"))),
+                None,
+                format!("scope must be one of {}", scopes.join(", ")),
+            )
+        }
+        ("scope-empty", false) => (
+            Target::Scope,
+            Some(".".to_string()),
+            None,
+            "scope may not be empty".to_string(),
+        ),
+        ("scope-empty", true) => (
+            Target::Scope,
+            None,
+            Some(".".to_string()),
+            "scope must be empty".to_string(),
+        ),
+        ("body-max-line-length", true) => {
+            let max = rule.number()?;
+            (
+                Target::Body,
+                None,
+                Some(format!(r"(?m)^.{{{},}}$", max + 1)),
+                format!("body lines must not be longer than {max} characters"),
+            )
+        }
+        ("body-leading-blank", true) => (
+            Target::Message,
+            None,
+            Some(r"\A.*\n.".to_string()),
+            "body must begin with a blank line".to_string(),
+        ),
+        // A footer without a blank line before it is left at the end of the body
+        ("footer-leading-blank", true) => (
+            Target::Body,
+            None,
+            Some(
+                r"(?mi)^(?:BREAKING[ -]CHANGE|[a-z-]+-by|refs?|close[sd]?|fix(?:e[sd])?|resolve[sd]?)(?:: | #).*\z"
+                    .to_string(),
+            ),
+            "footer must begin with a blank line".to_string(),
+        ),
+        _ => return None,
+    };
+    Some(CustomRuleConfig {
+        id: name.to_string(),
+        target: Some(target),
+        pattern,
+        forbid_pattern,
+        when: None,
+        severity: Some(rule.severity()),
+        message: Some(message),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::custom_rules;
+
+    fn convert_json(json: &str) -> Conversion {
+        convert(&serde_json::from_str(json).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn maps_native_rules() {
+        let conversion = convert_json(
+            r#"{"rules": {
+                "type-enum": [2, "always", ["feat", "fix"]],
+                "subject-full-stop": [2, "never", "."],
+                "subject-max-length": [1, "always", 60],
+                "subject-min-length": [2, "always", 3],
+                "header-max-length": [2, "always", 100],
+                "signed-off-by": [2, "always", "Signed-off-by:"],
+                "trailer-exists": [2, "always", "Reviewed-by:"],
+                "references-empty": [2, "never"],
+                "type-empty": [2, "never"],
+                "body-case": [0, "always", "lower-case"]
+            }}"#,
+        );
+        let config = &conversion.config;
+        assert_eq!(config.types, vec!["feat", "fix"]);
+        assert_eq!(config.no_trailing_period, Some(true));
+        assert_eq!(config.max_subject, Some(60));
+        assert_eq!(config.min_subject, Some(3));
+        assert_eq!(config.max_header, Some(100));
+        assert_eq!(config.signoff.required, Some(true));
+        assert_eq!(config.trailers.required, vec!["Reviewed-by"]);
+        assert_eq!(config.references.required_for, vec!["*"]);
+        assert_eq!(
+            conversion.notes,
+            vec!["rule `subject-max-length` is a warning in commitlint but an error in cc-check"]
+        );
+    }
+
+    #[test]
+    fn maps_other_rules_to_custom_rules() {
+        let conversion = convert_json(
+            r#"{"rules": {
+                "scope-enum": [2, "always", ["api", "web"]],
+                "body-max-line-length": [1, "always", 10]
+            }}"#,
+        );
+        let rules = custom_rules::compile(&conversion.config.custom_rules).unwrap();
+        let ids: Vec<_> = rules.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(ids, vec!["body-max-line-length", "scope-enum"]);
+        assert_eq!(rules[0].severity, Severity::Warning);
+
+        let scope = rules[1].pattern.as_ref().unwrap();
+        assert!(scope.is_match("api") && scope.is_match("") && !scope.is_match("apix"));
+        let body = rules[0].forbid_pattern.as_ref().unwrap();
+        assert!(body.is_match("short\nfar too long line") && !body.is_match("short\nlines"));
+    }
+
+    #[test]
+    fn subject_case_follows_commitlint_cases() {
+        let subject_case = |json: &str| {
+            let conversion = convert_json(&format!(r#"{{"rules": {{"subject-case": {json}}}}}"#));
+            let rules = custom_rules::compile(&conversion.config.custom_rules).unwrap();
+            let rule = rules.into_iter().next().unwrap();
+            move |subject: &str| {
+                rule.pattern.as_ref().is_none_or(|p| p.is_match(subject))
+                    && !rule
+                        .forbid_pattern
+                        .as_ref()
+                        .is_some_and(|p| p.is_match(subject))
+            }
+        };
+
+        let not_sentence_or_upper =
+            subject_case(r#"[2, "never", ["sentence-case", "upper-case"]]"#);
+        assert!(not_sentence_or_upper("add docs"));
+        assert!(not_sentence_or_upper("API docs"));
+        assert!(not_sentence_or_upper("README tweaks"));
+        assert!(!not_sentence_or_upper("Add docs"));
+        assert!(!not_sentence_or_upper("ADD DOCS"));
+
+        let lower = subject_case(r#"[2, "always", "lower-case"]"#);
+        assert!(lower("add docs"));
+        assert!(!lower("add API docs"));
+
+        let sentence = subject_case(r#"[2, "always", "sentence-case"]"#);
+        assert!(sentence("Add API docs"));
+        assert!(!sentence("API docs") && !sentence("add docs"));
+    }
+
+    #[test]
+    fn leading_blank_rules() {
+        let conversion = convert_json(
+            r#"{"rules": {"body-leading-blank": [2, "always"], "footer-leading-blank": [2, "always"]}}"#,
+        );
+        let rules = custom_rules::compile(&conversion.config.custom_rules).unwrap();
+        let body = rules[0].forbid_pattern.as_ref().unwrap();
+        assert!(body.is_match("feat: x\nbody") && !body.is_match("feat: x\n\nbody"));
+        assert!(!body.is_match("feat: x"));
+        let footer = rules[1].forbid_pattern.as_ref().unwrap();
+        assert!(footer.is_match("Some text.\nCloses #12"));
+        assert!(!footer.is_match("Closes #12 was wrong.\nSome text."));
+    }
+
+    #[test]
+    fn extends_config_conventional() {
+        let conversion = convert_json(
+            r#"{"extends": ["@commitlint/config-conventional", "@company/commitlint-config"],
+                "rules": {"header-max-length": [0], "magic-rule": [2, "always"]}}"#,
+        );
+        let config = &conversion.config;
+        assert_eq!(config.types.len(), 11);
+        assert_eq!(config.subject_case, None);
+        assert_eq!(config.max_subject, Some(0));
+        assert_eq!(config.max_header, None);
+        let ids: Vec<_> = config.custom_rules.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "body-leading-blank",
+                "body-max-line-length",
+                "footer-leading-blank",
+                "subject-case"
+            ]
+        );
+        assert_eq!(
+            conversion.notes,
+            vec![
+                "extends `@company/commitlint-config` is not supported and was ignored",
+                "rule `footer-max-line-length` is not supported and was ignored",
+                "rule `magic-rule` is not supported and was ignored",
+                "rule `subject-case` is only partly supported: start-case, pascal-case subjects are not rejected",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_yaml_and_refuses_javascript() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join(".commitlintrc.yml"),
+            "rules:\n  type-enum: [2, always, [feat, fix]]\n",
+        )
+        .unwrap();
+        let path = find_in_dir(dir.path()).unwrap().unwrap();
+        assert_eq!(load(&path).unwrap().config.types, vec!["feat", "fix"]);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            dir.path().join("commitlint.config.js"),
+            "module.exports = {}",
+        )
+        .unwrap();
+        assert!(find_in_dir(dir.path()).is_err());
+    }
+}
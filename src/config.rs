@@ -1,65 +1,1027 @@
-use anyhow::{Context, Result};
-use serde::Deserialize;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use crate::branch::BranchConfig;
+use crate::cleanup::CleanupMode;
+use crate::commitlint;
+use crate::content::{self, ContentRule};
+use crate::custom_rules::CustomRuleConfig;
+use crate::git_message::GitMessageKind;
+use crate::plugin::PluginConfig;
+use crate::references::ReferenceConfig;
+use crate::repo::RepoPaths;
+use crate::script::ScriptConfig;
+use crate::signoff::SignoffConfig;
+use crate::skip;
+use crate::trailers::TrailerConfig;
+use crate::types::{self, TypeDefinition, TypeInfo};
+use crate::{preset, HeaderRules, LengthUnit, SubjectCase, DEFAULT_HEADER_PATTERN, DEFAULT_TYPES};
 
 /// Configuration for cc-check, loaded from .cc-check.toml
-#[derive(Debug, Deserialize, Clone, Default)]
-#[serde(default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
 pub struct Config {
+    /// Base configs merged before this one, in order: paths relative to this file
+    /// or built-in presets written as "preset:<name>"
+    pub extends: Vec<String>,
+
+    /// Built-in convention providing the type list, header pattern, case rule and
+    /// subject limits (e.g. "angular", "eslint")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preset: Option<String>,
+
+    /// Complete list of allowed commit types, replacing the default (or preset) list
+    pub types: Vec<String>,
+
     /// Additional commit types to allow (e.g., ["wip", "release"])
+    /// Replaces the list inherited from `extends`
     pub extra_types: Vec<String>,
 
+    /// Additional commit types appended to the list inherited from `extends`
+    pub extra_types_add: Vec<String>,
+
+    /// Commit types to reject even if the type list includes them (e.g., ["chore"])
+    pub disallowed_types: Vec<String>,
+
+    /// Per-type metadata (description, changelog section, semver impact), keyed by type
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub type_info: BTreeMap<String, TypeInfo>,
+
     /// Maximum subject length (0 to disable)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_subject: Option<usize>,
 
+    /// Minimum subject length (0 to disable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_subject: Option<usize>,
+
+    /// Maximum length of the whole header line, `type(scope): ` included (0 to disable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_header: Option<usize>,
+
+    /// How lengths are measured: "chars" (default) or "columns", where East Asian wide
+    /// characters and emoji count as two
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub length_unit: Option<LengthUnit>,
+
     /// Disallow trailing period in subject
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub no_trailing_period: Option<bool>,
 
-    /// Ignore comment lines (starting with '#') in commit message
+    /// Ignore comment lines (starting with the comment char, '#' by default) in commit message
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub ignore_comments: Option<bool>,
 
-    /// Allow merge-like messages (e.g., 'Merge ...' or 'Revert ...') to pass
+    /// Message cleanup before validation, as git's `commit.cleanup`: "default" (use the
+    /// repository's setting), "strip", "whitespace", "verbatim" or "scissors"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cleanup: Option<CleanupMode>,
+
+    /// Comment prefix, or "auto"; defaults to git's `core.commentString`/`core.commentChar`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment_char: Option<String>,
+
+    /// Allow git-generated merge and revert messages (see `git_messages`) to pass
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub allow_merge_commits: Option<bool>,
+
+    /// Git-generated messages let through when `allow_merge_commits` is on, e.g.
+    /// ["merge-branch", "merge-pull-request"]; leave out "revert" to require `revert:`,
+    /// or set `[]` to let none through
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_messages: Option<Vec<GitMessageKind>>,
+
+    /// Issue-reference patterns and the commit types that must reference an issue
+    pub references: ReferenceConfig,
+
+    /// Opt-in check that the commit agrees with the branch name, e.g. `feat/PROJ-123-x`
+    pub branch: BranchConfig,
+
+    /// Opt-in DCO check for a `Signed-off-by: Name <email>` trailer
+    pub signoff: SignoffConfig,
+
+    /// Forbidden-content rules keyed by id: built-ins such as `wip` or `aws-access-key`,
+    /// or custom ones with a `pattern`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub content: BTreeMap<String, ContentRule>,
+
+    /// House rules: a regex a part of the message must (or must not) match
+    pub custom_rules: Vec<CustomRuleConfig>,
+
+    /// External rule executables speaking the plugin JSON protocol
+    pub plugins: Vec<PluginConfig>,
+
+    /// Rhai rule scripts, `.cc-check/rules/*.rhai` by default
+    pub scripts: ScriptConfig,
+
+    /// Allowed and required trailers, their value formats, order and duplicates
+    pub trailers: TrailerConfig,
+
+    /// Require `revert` commits to name the reverted commit in a `Refs:` footer or a
+    /// `This reverts commit <sha>.` line; `check --range` also verifies the commit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub require_revert_refs: Option<bool>,
+
+    /// Accept `fixup!`/`squash!`/`amend!` commits whose target header is valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_autosquash: Option<bool>,
+
+    /// Reject unsquashed `fixup!`/`squash!`/`amend!` commits in `check --range`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forbid_unsquashed: Option<bool>,
+
+    /// Let commits bypass validation with `CC_CHECK_SKIP=1` or the skip marker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_skip: Option<bool>,
+
+    /// Marker that bypasses validation when present in the message ("" to disable)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_marker: Option<String>,
+
+    /// Header regex with named groups `type`, `subject` and optionally `scope`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header_pattern: Option<String>,
+
+    /// Case rule for the subject: "any", "lower" or "sentence"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_case: Option<SubjectCase>,
+}
+
+/// Error raised when a config file is not valid TOML or contains unknown/mistyped keys
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Path of the offending config file
+    pub path: PathBuf,
+    /// 1-based line of the error
+    pub line: usize,
+    /// 1-based column of the error
+    pub column: usize,
+    /// Description of the problem
+    pub message: String,
+    /// Closest known key when the error is an unknown key
+    pub suggestion: Option<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}",
+            self.path.display(),
+            self.line,
+            self.column,
+            self.message
+        )?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " (did you mean `{suggestion}`?)")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl ConfigError {
+    fn from_toml(path: &Path, contents: &str, err: &toml::de::Error) -> Self {
+        let offset = err.span().map(|span| span.start).unwrap_or(0);
+        let (line, column) = line_and_column(contents, offset);
+        let (message, suggestion) = describe_serde_error(err.message());
+
+        Self {
+            path: path.to_path_buf(),
+            line,
+            column,
+            message,
+            suggestion,
+        }
+    }
+
+    fn from_json(path: &Path, err: &serde_json::Error) -> Self {
+        let full = err.to_string();
+        let location = format!(" at line {} column {}", err.line(), err.column());
+        let (message, suggestion) =
+            describe_serde_error(full.strip_suffix(&location).unwrap_or(&full));
+
+        Self {
+            path: path.to_path_buf(),
+            line: err.line(),
+            column: err.column(),
+            message,
+            suggestion,
+        }
+    }
+}
+
+/// Convert a byte offset into a 1-based (line, column) pair
+fn line_and_column(contents: &str, offset: usize) -> (usize, usize) {
+    let offset = offset.min(contents.len());
+    let before = &contents[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// Shorten serde's unknown-field message and pick the closest expected key, if any.
+///
+/// serde reports unknown keys as "unknown field `x`, expected one of `a`, `b`" (or
+/// "expected `a`" for a single candidate), which works for nested tables too.
+fn describe_serde_error(message: &str) -> (String, Option<String>) {
+    let message = message.trim().to_string();
+    let Some(rest) = message.strip_prefix("unknown field `") else {
+        return (message, None);
+    };
+    let Some((unknown, expected)) = rest.split_once('`') else {
+        return (message, None);
+    };
+
+    let candidates: Vec<&str> = expected.split('`').skip(1).step_by(2).collect();
+    let suggestion = candidates
+        .iter()
+        .map(|candidate| (strsim::jaro_winkler(unknown, candidate), *candidate))
+        .filter(|(score, _)| *score >= 0.8)
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, candidate)| candidate.to_string());
+
+    match suggestion {
+        Some(_) => (format!("unknown key `{unknown}`"), suggestion),
+        None => (format!("unknown key `{unknown}`{expected}"), None),
+    }
 }
 
 impl Config {
-    /// Load config from a TOML file
+    /// The built-in defaults, with every option set
+    pub fn defaults() -> Self {
+        Self {
+            extends: Vec::new(),
+            preset: None,
+            types: Vec::new(),
+            extra_types: Vec::new(),
+            extra_types_add: Vec::new(),
+            disallowed_types: Vec::new(),
+            type_info: BTreeMap::new(),
+            max_subject: Some(72),
+            min_subject: Some(0),
+            max_header: Some(0),
+            length_unit: Some(LengthUnit::Chars),
+            no_trailing_period: Some(true),
+            ignore_comments: Some(true),
+            cleanup: Some(CleanupMode::Default),
+            comment_char: None,
+            allow_merge_commits: Some(true),
+            git_messages: Some(GitMessageKind::ALL.to_vec()),
+            references: ReferenceConfig::defaults(),
+            branch: BranchConfig::defaults(),
+            signoff: SignoffConfig::defaults(),
+            content: content::default_rules(),
+            custom_rules: Vec::new(),
+            plugins: Vec::new(),
+            scripts: ScriptConfig::defaults(),
+            trailers: TrailerConfig::defaults(),
+            require_revert_refs: Some(false),
+            allow_autosquash: Some(true),
+            forbid_unsquashed: Some(false),
+            allow_skip: Some(true),
+            skip_marker: Some(skip::DEFAULT_SKIP_MARKER.to_string()),
+            header_pattern: Some(DEFAULT_HEADER_PATTERN.to_string()),
+            subject_case: Some(SubjectCase::Any),
+        }
+    }
+
+    /// Allowed types for this (merged) config: `types` if set, otherwise the preset's
+    /// types (or the default Angular list), plus `extra_types`, minus `disallowed_types`.
+    /// An empty result means any type is allowed.
+    pub fn allowed_types(&self) -> Result<Vec<String>> {
+        let mut allowed_types: Vec<String> = if !self.types.is_empty() {
+            self.types.clone()
+        } else {
+            let base_types = match &self.preset {
+                Some(name) => find_preset(name)?.types,
+                None => DEFAULT_TYPES,
+            };
+            base_types.iter().map(|t| t.to_string()).collect()
+        };
+
+        if !allowed_types.is_empty() {
+            for extra in &self.extra_types {
+                if !allowed_types.contains(extra) {
+                    allowed_types.push(extra.clone());
+                }
+            }
+        }
+        allowed_types.retain(|t| !self.disallowed_types.contains(t));
+
+        Ok(allowed_types)
+    }
+
+    /// Allowed types with their metadata; `type_info` overrides the built-in metadata
+    pub fn type_definitions(&self) -> Result<Vec<TypeDefinition>> {
+        Ok(self
+            .allowed_types()?
+            .into_iter()
+            .map(|name| {
+                let configured = self.type_info.get(&name).cloned().unwrap_or_default();
+                let info = configured.or(types::builtin_info(&name));
+                TypeDefinition { name, info }
+            })
+            .collect())
+    }
+
+    /// Header rules for this (merged) config: the allowed types and the configured
+    /// pattern and limits
+    pub fn header_rules(&self) -> Result<HeaderRules> {
+        let allowed_types = self.allowed_types()?;
+
+        let pattern_source = self
+            .header_pattern
+            .as_deref()
+            .unwrap_or(DEFAULT_HEADER_PATTERN);
+        let pattern = regex::Regex::new(pattern_source)
+            .with_context(|| format!("invalid header_pattern `{pattern_source}`"))?;
+        for group in ["type", "subject"] {
+            if !pattern.capture_names().flatten().any(|name| name == group) {
+                bail!("header_pattern `{pattern_source}` has no named group `{group}`");
+            }
+        }
+
+        Ok(HeaderRules {
+            pattern,
+            allowed_types,
+            disallowed_types: self.disallowed_types.clone(),
+            max_subject: self.max_subject.unwrap_or(72),
+            min_subject: self.min_subject.unwrap_or(0),
+            max_header: self.max_header.unwrap_or(0),
+            length_unit: self.length_unit.unwrap_or_default(),
+            no_trailing_period: self.no_trailing_period.unwrap_or(true),
+            subject_case: self.subject_case.unwrap_or_default(),
+        })
+    }
+
+    /// Load config from a file: a plain TOML config, the cc-check section of a
+    /// `pyproject.toml`, `package.json` or `Cargo.toml`, or a commitlint config
     pub fn from_file(path: &Path) -> Result<Self> {
+        Ok(Self::from_file_with_notes(path)?.0)
+    }
+
+    /// Like [`Config::from_file`], also returning what could not be carried over from
+    /// a commitlint config (always empty for cc-check's own formats)
+    pub fn from_file_with_notes(path: &Path) -> Result<(Self, Vec<String>)> {
+        Self::from_host_file(path)?.with_context(|| {
+            format!(
+                "{} has no {} section",
+                path.display(),
+                HostFile::of(path).section()
+            )
+        })
+    }
+
+    /// Load config from a file, with the notes of a commitlint conversion, returning
+    /// None for a host file without a cc-check section
+    fn from_host_file(path: &Path) -> Result<Option<(Self, Vec<String>)>> {
         let contents = std::fs::read_to_string(path)
             .with_context(|| format!("failed to read config file: {}", path.display()))?;
 
-        toml::from_str(&contents)
-            .with_context(|| format!("failed to parse config file: {}", path.display()))
+        let toml_err = |err: toml::de::Error| ConfigError::from_toml(path, &contents, &err);
+        let mut notes = Vec::new();
+        let config = match HostFile::of(path) {
+            HostFile::Standalone => Some(Self::parse(&contents, path)?),
+            HostFile::PyProject => toml::from_str::<PyProject>(&contents)
+                .map_err(toml_err)?
+                .tool
+                .and_then(|tool| tool.cc_check),
+            HostFile::Cargo => {
+                let manifest = toml::from_str::<CargoManifest>(&contents).map_err(toml_err)?;
+                [manifest.package, manifest.workspace]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|section| section.metadata.and_then(|m| m.cc_check))
+            }
+            HostFile::PackageJson => {
+                serde_json::from_str::<PackageJson>(&contents)
+                    .map_err(|err| ConfigError::from_json(path, &err))?
+                    .cc_check
+            }
+            HostFile::Commitlint => {
+                let conversion = commitlint::parse(&contents, path)?;
+                notes = conversion.notes;
+                Some(conversion.config)
+            }
+        };
+        Ok(config.map(|config| (config, notes)))
+    }
+
+    /// Parse config from TOML source, reporting errors against `path`
+    pub fn parse(contents: &str, path: &Path) -> Result<Self, ConfigError> {
+        toml::from_str(contents).map_err(|err| ConfigError::from_toml(path, contents, &err))
+    }
+
+    /// Render as a standalone TOML config, leaving out everything that is unset
+    pub fn to_toml(&self) -> Result<String> {
+        let mut table = to_table(self)?;
+        strip_unset(&mut table);
+        Ok(toml::to_string(&table)?)
     }
 
     /// Find and load config file from repository root
     /// Returns None if no config file is found
     pub fn load_from_repo() -> Result<Option<Self>> {
-        let repo_root = crate::find_repo_root().ok();
+        match Self::find_config_file() {
+            Some(path) => Ok(Some(Self::from_file(&path)?)),
+            None => Ok(None),
+        }
+    }
 
-        if let Some(root) = repo_root {
-            let config_path = root.join(".cc-check.toml");
-            if config_path.exists() {
-                return Ok(Some(Self::from_file(&config_path)?));
+    /// Find the config file for the current directory
+    ///
+    /// `CC_CHECK_CONFIG` wins if set; otherwise each directory from the current one up
+    /// to the repository boundary is searched for the names in [`CONFIG_FILE_NAMES`].
+    pub fn find_config_file() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_ENV_VAR).filter(|p| !p.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+        let current_dir = std::env::current_dir().ok()?;
+        Self::find_config_file_from(&current_dir, &RepoPaths::discover(&current_dir))
+    }
+
+    /// Search `start` and its ancestors for a config file, stopping at the git root
+    /// (or, outside git, the nearest package root)
+    pub fn find_config_file_from(start: &Path, repo: &RepoPaths) -> Option<PathBuf> {
+        let boundary = repo.boundary();
+        for dir in start.ancestors() {
+            if let Some(found) = Self::find_config_in_dir(dir) {
+                return Some(found);
+            }
+            if boundary.is_none_or(|boundary| same_dir(dir, boundary)) {
+                break;
             }
         }
+        None
+    }
 
-        Ok(None)
+    /// The config file directly in `dir`, if any
+    pub fn find_config_in_dir(dir: &Path) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|candidate| candidate.is_file() && Self::provides_config(candidate))
     }
 
-    /// Find the config file path in the repository
-    pub fn find_config_file() -> Option<PathBuf> {
-        let repo_root = crate::find_repo_root().ok()?;
-        let config_path = repo_root.join(".cc-check.toml");
+    /// Config files that apply to changes under `dir` (relative to `git_root`): the
+    /// config of every directory from the git root down to `dir`, root first
+    pub fn find_config_chain(git_root: &Path, dir: &Path) -> Vec<PathBuf> {
+        let mut current = git_root.to_path_buf();
+        let mut chain: Vec<PathBuf> = Self::find_config_in_dir(&current).into_iter().collect();
+        for component in dir.components() {
+            current.push(component);
+            chain.extend(Self::find_config_in_dir(&current));
+        }
+        chain
+    }
+
+    /// Whether a candidate file holds cc-check config; host files that can't be read or
+    /// parsed are someone else's problem, but a broken cc-check section still counts, as
+    /// does a `package.json` that mentions a `"cc-check"` key but fails to parse (so its
+    /// syntax error is reported when it's loaded)
+    fn provides_config(path: &Path) -> bool {
+        let Ok(contents) = std::fs::read_to_string(path) else {
+            return false;
+        };
+        match HostFile::of(path) {
+            HostFile::Standalone | HostFile::Commitlint => true,
+            HostFile::PackageJson => match serde_json::from_str::<serde_json::Value>(&contents) {
+                Ok(json) => json.get("cc-check").is_some(),
+                Err(_) => contents.contains("\"cc-check\""),
+            },
+            host => contents.parse::<Table>().is_ok_and(|table| {
+                host.toml_sections()
+                    .iter()
+                    .any(|section| lookup(&table, section).is_some())
+            }),
+        }
+    }
+}
+
+/// Compare directories, tolerating symlinks (e.g. git reporting /private/var on macOS)
+fn same_dir(a: &Path, b: &Path) -> bool {
+    a == b
+        || match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => false,
+        }
+}
+
+/// Environment variable naming an explicit config file
+pub const CONFIG_ENV_VAR: &str = "CC_CHECK_CONFIG";
+
+/// Environment variable enabling deprecation warnings for legacy invocations
+pub const DEPRECATIONS_ENV_VAR: &str = "CC_CHECK_DEPRECATIONS";
+
+/// Prefix of environment variables overriding config keys, e.g. `CC_CHECK_MAX_SUBJECT`
+pub const ENV_PREFIX: &str = "CC_CHECK_";
+
+/// `CC_CHECK_*` variables that control cc-check itself rather than a config key
+/// (`CC_CHECK_FORMAT` is the default for `check --format`)
+const RESERVED_ENV_VARS: &[&str] = &[
+    CONFIG_ENV_VAR,
+    DEPRECATIONS_ENV_VAR,
+    skip::SKIP_ENV_VAR,
+    "CC_CHECK_FORMAT",
+];
 
-        if config_path.exists() {
-            Some(config_path)
+impl Config {
+    /// Config layer from `CC_CHECK_<KEY>` environment variables
+    ///
+    /// Variables that aren't valid UTF-8 are ignored unless they are `CC_CHECK_*`
+    /// variables, whose values must be UTF-8.
+    pub fn from_env() -> Result<Self> {
+        let mut vars = Vec::new();
+        for (name, value) in std::env::vars_os() {
+            let Some(name) = name.to_str().filter(|name| name.starts_with(ENV_PREFIX)) else {
+                continue;
+            };
+            let Ok(value) = value.into_string() else {
+                bail!("invalid value in {name}: not valid UTF-8");
+            };
+            vars.push((name.to_string(), value));
+        }
+        Self::from_env_vars(vars)
+    }
+
+    /// Config layer from the `CC_CHECK_<KEY>` entries of `vars`
+    ///
+    /// List keys take comma-separated values (`CC_CHECK_EXTRA_TYPES=wip,release`),
+    /// numbers and booleans are parsed, and tables such as `CC_CHECK_TYPE_INFO` take
+    /// an inline TOML table. Unknown variables are rejected with a suggestion.
+    pub fn from_env_vars(vars: impl IntoIterator<Item = (String, String)>) -> Result<Self> {
+        let defaults = to_table(&Config::defaults())?;
+        let mut table = Table::new();
+
+        for (name, raw) in vars {
+            let Some(suffix) = name.strip_prefix(ENV_PREFIX) else {
+                continue;
+            };
+            if RESERVED_ENV_VARS.contains(&name.as_str()) {
+                continue;
+            }
+            let key = suffix.to_lowercase();
+            // `extends` is resolved while loading config files, so it can't come from here
+            if key == "extends" {
+                bail!("unknown environment variable {name}");
+            }
+            let value = env_value(&raw, defaults.get(&key))
+                .with_context(|| format!("invalid value in {name}"))?;
+
+            // Check each variable on its own so errors name the variable at fault
+            let mut single = Table::new();
+            single.insert(key.clone(), value.clone());
+            if let Err(err) = Config::deserialize(Value::Table(single)) {
+                let (message, suggestion) = describe_serde_error(&err.to_string());
+                match suggestion {
+                    Some(suggestion) => bail!(
+                        "unknown environment variable {name} (did you mean {}?)",
+                        env_var(&suggestion)
+                    ),
+                    None if message.starts_with("unknown key") => {
+                        bail!("unknown environment variable {name}")
+                    }
+                    None => bail!("invalid value in {name}: {message}"),
+                }
+            }
+            table.insert(key, value);
+        }
+
+        Ok(Config::deserialize(Value::Table(table))?)
+    }
+}
+
+/// Interpret a raw environment value according to the type of the key's default
+fn env_value(raw: &str, default: Option<&Value>) -> Result<Value> {
+    let raw = raw.trim();
+    let parse_toml = |raw: &str| -> Result<Value> {
+        let mut table: Table = format!("v = {raw}").parse()?;
+        Ok(table.remove("v").unwrap_or(Value::String(raw.to_string())))
+    };
+
+    match default {
+        Some(Value::Array(_)) if raw.starts_with('[') => parse_toml(raw),
+        Some(Value::Array(_)) => Ok(Value::Array(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(|item| Value::String(item.to_string()))
+                .collect(),
+        )),
+        Some(Value::Integer(_) | Value::Boolean(_)) => parse_toml(raw),
+        _ if raw.starts_with('{') => parse_toml(raw),
+        _ => Ok(Value::String(raw.to_string())),
+    }
+}
+
+/// Files searched for configuration in each directory, in order of preference
+pub const CONFIG_FILE_NAMES: &[&str] = &[
+    ".cc-check.toml",
+    "cc-check.toml",
+    ".config/cc-check.toml",
+    "pyproject.toml",
+    "package.json",
+    "Cargo.toml",
+    ".commitlintrc",
+    ".commitlintrc.json",
+    ".commitlintrc.yaml",
+    ".commitlintrc.yml",
+];
+
+/// Kind of file a config is read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HostFile {
+    /// A dedicated cc-check TOML file
+    Standalone,
+    /// `[tool.cc-check]` in pyproject.toml
+    PyProject,
+    /// `"cc-check"` in package.json
+    PackageJson,
+    /// `[package.metadata.cc-check]` or `[workspace.metadata.cc-check]` in Cargo.toml
+    Cargo,
+    /// A commitlint JSON or YAML config, mapped onto cc-check's options
+    Commitlint,
+}
+
+impl HostFile {
+    fn of(path: &Path) -> Self {
+        match path.file_name().and_then(|name| name.to_str()) {
+            Some("pyproject.toml") => HostFile::PyProject,
+            Some("package.json") => HostFile::PackageJson,
+            Some("Cargo.toml") => HostFile::Cargo,
+            _ if commitlint::is_config_file(path) => HostFile::Commitlint,
+            _ => HostFile::Standalone,
+        }
+    }
+
+    /// Dotted paths of the cc-check section in TOML host files
+    fn toml_sections(self) -> &'static [&'static str] {
+        match self {
+            HostFile::PyProject => &["tool.cc-check"],
+            HostFile::Cargo => &["package.metadata.cc-check", "workspace.metadata.cc-check"],
+            HostFile::Standalone | HostFile::PackageJson | HostFile::Commitlint => &[],
+        }
+    }
+
+    fn section(self) -> &'static str {
+        match self {
+            HostFile::Standalone | HostFile::Commitlint => "cc-check",
+            HostFile::PyProject => "[tool.cc-check]",
+            HostFile::PackageJson => "\"cc-check\"",
+            HostFile::Cargo => "[package.metadata.cc-check] or [workspace.metadata.cc-check]",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct PyProject {
+    tool: Option<PyProjectTool>,
+}
+
+#[derive(Deserialize)]
+struct PyProjectTool {
+    #[serde(rename = "cc-check")]
+    cc_check: Option<Config>,
+}
+
+#[derive(Deserialize)]
+struct CargoManifest {
+    package: Option<CargoSection>,
+    workspace: Option<CargoSection>,
+}
+
+#[derive(Deserialize)]
+struct CargoSection {
+    metadata: Option<CargoMetadata>,
+}
+
+#[derive(Deserialize)]
+struct CargoMetadata {
+    #[serde(rename = "cc-check")]
+    cc_check: Option<Config>,
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    #[serde(rename = "cc-check")]
+    cc_check: Option<Config>,
+}
+
+/// Where an effective configuration value was taken from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// Built-in default
+    Default,
+    /// A built-in preset
+    Preset(String),
+    /// A configuration file
+    File(PathBuf),
+    /// A `CC_CHECK_*` environment variable
+    Env,
+    /// A command-line flag
+    Cli,
+}
+
+impl Origin {
+    /// Human-readable description of the origin of `key`
+    pub fn describe(&self, key: &str) -> String {
+        match self {
+            Origin::Default => "default".to_string(),
+            Origin::Preset(name) => format!("preset {name}"),
+            Origin::File(path) => path.display().to_string(),
+            Origin::Env => format!("env {}", env_var(key)),
+            Origin::Cli => format!("CLI flag {}", cli_flag(key)),
+        }
+    }
+}
+
+/// The environment variable corresponding to a (possibly dotted) config key; nested
+/// keys are set through their top-level table, e.g. `CC_CHECK_TYPE_INFO`
+pub fn env_var(key: &str) -> String {
+    let top_level = key.split('.').next().unwrap_or(key);
+    format!("{ENV_PREFIX}{}", top_level.to_uppercase())
+}
+
+/// The command-line flag corresponding to a (possibly dotted) config key
+pub fn cli_flag(key: &str) -> String {
+    format!("--{}", key.replace(['_', '.'], "-"))
+}
+
+/// A single effective configuration value and where it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigEntry {
+    /// Dotted key, e.g. `max_subject`
+    pub key: String,
+    /// Effective value
+    pub value: Value,
+    /// Layer that provided the value
+    pub origin: Origin,
+}
+
+/// Configuration assembled from layers of increasing precedence
+/// (defaults, then the selected preset, then config files, then environment
+/// variables, then CLI flags)
+#[derive(Debug, Clone)]
+pub struct ResolvedConfig {
+    layers: Vec<(Origin, Table)>,
+    /// Parts of commitlint configs that could not be carried over, as `<path>: <note>`
+    notes: Vec<String>,
+}
+
+impl Default for ResolvedConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResolvedConfig {
+    /// Start from the built-in defaults
+    pub fn new() -> Self {
+        let defaults = to_table(&Config::defaults()).expect("defaults serialize to a table");
+        Self {
+            layers: vec![(Origin::Default, defaults)],
+            notes: Vec::new(),
+        }
+    }
+
+    /// Add a layer that overrides every value set so far
+    pub fn push(&mut self, origin: Origin, config: &Config) -> Result<()> {
+        let mut table = to_table(config)?;
+        strip_unset(&mut table);
+        self.layers.push((origin, table));
+        Ok(())
+    }
+
+    /// Add a config file as a layer, preceded by everything it `extends`
+    pub fn push_file(&mut self, path: &Path) -> Result<()> {
+        self.push_file_inner(path, &mut Vec::new())
+    }
+
+    fn push_file_inner(&mut self, path: &Path, chain: &mut Vec<PathBuf>) -> Result<()> {
+        let canonical = std::fs::canonicalize(path)
+            .with_context(|| format!("failed to read config file: {}", path.display()))?;
+        if chain.contains(&canonical) {
+            let cycle: Vec<String> = chain
+                .iter()
+                .chain(std::iter::once(&canonical))
+                .map(|p| p.display().to_string())
+                .collect();
+            bail!("config extends cycle: {}", cycle.join(" -> "));
+        }
+
+        let (config, notes) = Config::from_file_with_notes(path)?;
+        self.notes.extend(
+            notes
+                .into_iter()
+                .map(|note| format!("{}: {note}", path.display())),
+        );
+        chain.push(canonical);
+        let base_dir = path.parent().unwrap_or(Path::new("."));
+        for base in &config.extends {
+            if let Some(name) = base.strip_prefix("preset:") {
+                self.push_preset(name)
+                    .with_context(|| format!("in `extends` of {}", path.display()))?;
+            } else {
+                self.push_file_inner(&base_dir.join(base), chain)
+                    .with_context(|| format!("in `extends` of {}", path.display()))?;
+            }
+        }
+        chain.pop();
+
+        self.push(Origin::File(path.to_path_buf()), &config)
+    }
+
+    /// `extends = ["preset:<name>"]` adds the preset as a layer at its position in
+    /// `extends`, so later entries and the extending file override it
+    fn push_preset(&mut self, name: &str) -> Result<()> {
+        let mut table = to_table(&find_preset(name)?.to_config())?;
+        strip_unset(&mut table);
+        self.layers.push((Origin::Preset(name.to_string()), table));
+        Ok(())
+    }
+
+    /// What the loaded commitlint configs contained that cc-check does not support
+    pub fn notes(&self) -> &[String] {
+        &self.notes
+    }
+
+    /// The merged configuration
+    pub fn config(&self) -> Result<Config> {
+        let layers = self.effective_layers()?;
+        Ok(Value::Table(merge_layers(&layers)).try_into()?)
+    }
+
+    /// Every effective value (flattened to dotted keys) with the layer it came from
+    pub fn entries(&self) -> Result<Vec<ConfigEntry>> {
+        let layers = self.effective_layers()?;
+        let mut values = Vec::new();
+        flatten("", &merge_layers(&layers), &mut values);
+
+        Ok(values
+            .into_iter()
+            .map(|(key, value)| {
+                let origin = layers
+                    .iter()
+                    .rev()
+                    .find(|(_, table)| {
+                        lookup(table, &key).is_some()
+                            || lookup(table, &format!("{key}{APPEND_SUFFIX}")).is_some()
+                    })
+                    .map(|(origin, _)| origin.clone())
+                    .unwrap_or(Origin::Default);
+                ConfigEntry { key, value, origin }
+            })
+            .collect())
+    }
+
+    /// The layers with a preset selected by `preset` (rather than `extends`) inserted
+    /// directly above the defaults, so anything set in a config file or on the command
+    /// line overrides the preset
+    fn effective_layers(&self) -> Result<Vec<(Origin, Table)>> {
+        let mut layers = self.layers.clone();
+        let selected = merge_layers(&layers)
+            .get("preset")
+            .and_then(Value::as_str)
+            .map(String::from);
+        let extended = |name: &str| {
+            layers
+                .iter()
+                .any(|(origin, _)| matches!(origin, Origin::Preset(p) if p == name))
+        };
+        if let Some(name) = selected.filter(|name| !extended(name)) {
+            let mut table = to_table(&find_preset(&name)?.to_config())?;
+            table.remove("preset");
+            strip_unset(&mut table);
+            layers.insert(1, (Origin::Preset(name), table));
+        }
+        Ok(layers)
+    }
+}
+
+fn find_preset(name: &str) -> Result<&'static preset::Preset> {
+    preset::find(name).ok_or_else(|| {
+        anyhow::anyhow!(
+            "unknown preset `{name}` (available: {})",
+            preset::names().join(", ")
+        )
+    })
+}
+
+fn merge_layers(layers: &[(Origin, Table)]) -> Table {
+    let mut merged = Table::new();
+    for (_, table) in layers {
+        merge_tables(&mut merged, table.clone());
+    }
+    merged
+}
+
+/// Serialize a config layer; `extends` is consumed while loading, so it is dropped here
+fn to_table(config: &Config) -> Result<Table> {
+    match Value::try_from(config)? {
+        Value::Table(mut table) => {
+            table.remove("extends");
+            Ok(table)
+        }
+        other => anyhow::bail!(
+            "config serialized to {} instead of a table",
+            other.type_str()
+        ),
+    }
+}
+
+/// Remove values that mean "not set" so they don't shadow lower layers
+///
+/// An empty array is only "not set" where the default is empty too; where it isn't
+/// (e.g. `git_messages = []`), the field is an `Option` and the empty array was given
+/// explicitly to override the default.
+fn strip_unset(table: &mut Table) {
+    let defaults = to_table(&Config::defaults()).expect("defaults serialize to a table");
+    strip_unset_against(table, Some(&defaults));
+}
+
+fn strip_unset_against(table: &mut Table, defaults: Option<&Table>) {
+    table.retain(|key, value| {
+        let default = defaults.and_then(|defaults| defaults.get(key));
+        match value {
+            Value::Array(items) => {
+                !items.is_empty()
+                    || default
+                        .and_then(Value::as_array)
+                        .is_some_and(|default| !default.is_empty())
+            }
+            Value::Table(inner) => {
+                strip_unset_against(inner, default.and_then(Value::as_table));
+                !inner.is_empty()
+            }
+            _ => true,
+        }
+    });
+}
+
+/// Suffix of keys whose array is appended to, rather than replacing, the inherited one
+const APPEND_SUFFIX: &str = "_add";
+
+/// Merge `overlay` into `base`; nested tables merge key by key, `<key>_add` arrays are
+/// appended to `<key>`, and everything else is replaced
+fn merge_tables(base: &mut Table, overlay: Table) {
+    let mut appends = Vec::new();
+    for (key, value) in overlay {
+        if let (Some(target), Value::Array(items)) = (key.strip_suffix(APPEND_SUFFIX), &value) {
+            appends.push((target.to_string(), items.clone()));
+            continue;
+        }
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(inner)) => merge_tables(existing, inner),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+
+    // Appends run after replacements so `extra_types` and `extra_types_add` in the
+    // same file behave as "replace, then add"
+    for (key, items) in appends {
+        let entry = base.entry(key).or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(existing) = entry {
+            for item in items {
+                if !existing.contains(&item) {
+                    existing.push(item);
+                }
+            }
+        }
+    }
+}
+
+fn flatten(prefix: &str, table: &Table, out: &mut Vec<(String, Value)>) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key.clone()
         } else {
-            None
+            format!("{prefix}.{key}")
+        };
+        match value {
+            Value::Table(inner) => flatten(&key, inner, out),
+            value => out.push((key, value.clone())),
         }
     }
 }
 
+fn lookup<'a>(table: &'a Table, dotted_key: &str) -> Option<&'a Value> {
+    let mut parts = dotted_key.split('.');
+    let mut value = table.get(parts.next()?)?;
+    for part in parts {
+        value = value.as_table()?.get(part)?;
+    }
+    Some(value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -118,6 +1080,488 @@ mod tests {
         assert_eq!(config.max_subject, Some(60));
     }
 
+    #[test]
+    fn rejects_unknown_key_with_suggestion() {
+        let err = Config::parse("extra_types = []\nmax_subjet = 50\n", Path::new("cfg.toml"))
+            .unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.column, 1);
+        assert_eq!(err.suggestion.as_deref(), Some("max_subject"));
+        assert_eq!(
+            err.to_string(),
+            "cfg.toml:2:1: unknown key `max_subjet` (did you mean `max_subject`?)"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_key_without_close_match() {
+        let err = Config::parse("colour = \"red\"", Path::new("cfg.toml")).unwrap_err();
+        assert_eq!(err.suggestion, None);
+        assert!(err
+            .message
+            .starts_with("unknown key `colour`, expected one of"));
+    }
+
+    #[test]
+    fn reports_location_of_type_errors() {
+        let err = Config::parse("\nmax_subject = \"long\"", Path::new("cfg.toml")).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 15));
+        assert!(err.message.contains("expected usize"));
+    }
+
+    #[test]
+    fn resolved_config_applies_layers_in_order() {
+        let mut resolved = ResolvedConfig::new();
+        let file: Config = toml::from_str("max_subject = 50\nextra_types = [\"wip\"]").unwrap();
+        resolved
+            .push(Origin::File(PathBuf::from(".cc-check.toml")), &file)
+            .unwrap();
+        let cli = Config {
+            max_subject: Some(60),
+            ..Config::default()
+        };
+        resolved.push(Origin::Cli, &cli).unwrap();
+
+        let config = resolved.config().unwrap();
+        assert_eq!(config.max_subject, Some(60));
+        assert_eq!(config.extra_types, vec!["wip"]);
+        assert_eq!(config.no_trailing_period, Some(true));
+    }
+
+    #[test]
+    fn resolved_config_reports_origins() {
+        let mut resolved = ResolvedConfig::new();
+        let file: Config = toml::from_str("max_subject = 50").unwrap();
+        resolved
+            .push(Origin::File(PathBuf::from("a.toml")), &file)
+            .unwrap();
+        resolved
+            .push(
+                Origin::Cli,
+                &Config {
+                    ignore_comments: Some(false),
+                    ..Config::default()
+                },
+            )
+            .unwrap();
+
+        let entries = resolved.entries().unwrap();
+        let origin_of = |key: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.key == key)
+                .map(|entry| entry.origin.clone())
+                .unwrap()
+        };
+        assert_eq!(
+            origin_of("max_subject"),
+            Origin::File(PathBuf::from("a.toml"))
+        );
+        assert_eq!(origin_of("ignore_comments"), Origin::Cli);
+        assert_eq!(origin_of("extra_types"), Origin::Default);
+        assert_eq!(
+            Origin::Cli.describe("ignore_comments"),
+            "CLI flag --ignore-comments"
+        );
+    }
+
+    #[test]
+    fn extends_merges_base_configs_in_order() {
+        let temp_dir = TempDir::new().unwrap();
+        let shared = temp_dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::write(
+            shared.join("base.toml"),
+            "extra_types = [\"wip\"]\nmax_subject = 50\nignore_comments = false",
+        )
+        .unwrap();
+        std::fs::write(
+            shared.join("strict.toml"),
+            "extends = [\"base.toml\"]\nmax_subject = 40",
+        )
+        .unwrap();
+        let config_path = temp_dir.path().join(".cc-check.toml");
+        std::fs::write(
+            &config_path,
+            "extends = [\"preset:angular\", \"shared/strict.toml\"]\nextra_types_add = [\"release\"]",
+        )
+        .unwrap();
+
+        let mut resolved = ResolvedConfig::new();
+        resolved.push_file(&config_path).unwrap();
+        let config = resolved.config().unwrap();
+
+        assert_eq!(config.extra_types, vec!["wip", "release"]);
+        assert_eq!(config.max_subject, Some(40));
+        assert_eq!(config.ignore_comments, Some(false));
+        assert!(config.extends.is_empty());
+    }
+
+    #[test]
+    fn extended_preset_applies_at_its_position() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("base.toml"),
+            "max_subject = 50\nno_trailing_period = false",
+        )
+        .unwrap();
+        let config_path = temp_dir.path().join(".cc-check.toml");
+        std::fs::write(
+            &config_path,
+            "extends = [\"./base.toml\", \"preset:angular\"]\nmax_subject = 60",
+        )
+        .unwrap();
+
+        let mut resolved = ResolvedConfig::new();
+        resolved.push_file(&config_path).unwrap();
+        let config = resolved.config().unwrap();
+
+        assert_eq!(config.no_trailing_period, Some(true));
+        assert_eq!(config.max_subject, Some(60));
+        let entries = resolved.entries().unwrap();
+        let trailing = entries
+            .iter()
+            .find(|entry| entry.key == "no_trailing_period")
+            .unwrap();
+        assert_eq!(trailing.origin, Origin::Preset("angular".to_string()));
+    }
+
+    #[test]
+    fn extra_types_replaces_inherited_list() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("base.toml"), "extra_types = [\"wip\"]").unwrap();
+        let config_path = temp_dir.path().join(".cc-check.toml");
+        std::fs::write(
+            &config_path,
+            "extends = [\"base.toml\"]\nextra_types = [\"release\"]",
+        )
+        .unwrap();
+
+        let mut resolved = ResolvedConfig::new();
+        resolved.push_file(&config_path).unwrap();
+        assert_eq!(resolved.config().unwrap().extra_types, vec!["release"]);
+    }
+
+    #[test]
+    fn extends_detects_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let a = temp_dir.path().join("a.toml");
+        std::fs::write(&a, "extends = [\"b.toml\"]").unwrap();
+        std::fs::write(temp_dir.path().join("b.toml"), "extends = [\"a.toml\"]").unwrap();
+
+        let err = ResolvedConfig::new().push_file(&a).unwrap_err();
+        assert!(format!("{err:#}").contains("config extends cycle"));
+    }
+
+    #[test]
+    fn extends_rejects_unknown_preset() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join(".cc-check.toml");
+        std::fs::write(&config_path, "extends = [\"preset:nope\"]").unwrap();
+
+        let err = ResolvedConfig::new().push_file(&config_path).unwrap_err();
+        assert!(format!("{err:#}").contains("unknown preset `nope`"));
+    }
+
+    #[test]
+    fn preset_sits_below_config_file_values() {
+        let mut resolved = ResolvedConfig::new();
+        let file: Config = toml::from_str("preset = \"eslint\"\nmax_subject = 60").unwrap();
+        resolved
+            .push(Origin::File(PathBuf::from("a.toml")), &file)
+            .unwrap();
+
+        let config = resolved.config().unwrap();
+        assert_eq!(config.max_subject, Some(60));
+        assert_eq!(config.no_trailing_period, Some(false));
+
+        let rules = config.header_rules().unwrap();
+        assert!(rules.allowed_types.contains(&"Fix".to_string()));
+        assert!(!rules.allowed_types.contains(&"feat".to_string()));
+
+        let entries = resolved.entries().unwrap();
+        let trailing = entries
+            .iter()
+            .find(|entry| entry.key == "no_trailing_period")
+            .unwrap();
+        assert_eq!(trailing.origin, Origin::Preset("eslint".to_string()));
+    }
+
+    #[test]
+    fn types_replaces_default_list_and_disallowed_types_removes() {
+        let config: Config = toml::from_str(
+            r#"
+            types = ["feat", "fix", "chore"]
+            extra_types = ["release"]
+            disallowed_types = ["chore"]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.allowed_types().unwrap(),
+            vec!["feat", "fix", "release"]
+        );
+
+        let config: Config = toml::from_str("disallowed_types = [\"chore\", \"style\"]").unwrap();
+        let allowed = config.allowed_types().unwrap();
+        assert!(allowed.contains(&"feat".to_string()));
+        assert!(!allowed.contains(&"chore".to_string()));
+        assert!(!allowed.contains(&"style".to_string()));
+    }
+
+    #[test]
+    fn type_definitions_merge_configured_metadata() {
+        let config: Config = toml::from_str(
+            r#"
+            types = ["feat", "deps"]
+
+            [type_info.deps]
+            description = "Dependency updates"
+            changelog_section = "Dependencies"
+            semver = "patch"
+
+            [type_info.feat]
+            changelog_section = "New Features"
+        "#,
+        )
+        .unwrap();
+
+        let definitions = config.type_definitions().unwrap();
+        assert_eq!(definitions[0].name, "feat");
+        assert_eq!(
+            definitions[0].info.changelog_section.as_deref(),
+            Some("New Features")
+        );
+        assert_eq!(
+            definitions[0].info.semver,
+            Some(crate::types::SemverImpact::Minor)
+        );
+        assert_eq!(
+            definitions[1].info.description.as_deref(),
+            Some("Dependency updates")
+        );
+    }
+
+    #[test]
+    fn header_rules_reject_pattern_without_subject_group() {
+        let config = Config {
+            header_pattern: Some("^(?P<type>[a-z]+): .+$".to_string()),
+            ..Config::defaults()
+        };
+        let err = config.header_rules().unwrap_err();
+        assert!(err.to_string().contains("no named group `subject`"));
+    }
+
+    #[test]
+    fn loads_config_from_pyproject() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("pyproject.toml");
+        std::fs::write(
+            &path,
+            "[project]\nname = \"x\"\n\n[tool.cc-check]\nmax_subject = 55\n",
+        )
+        .unwrap();
+
+        assert_eq!(Config::from_file(&path).unwrap().max_subject, Some(55));
+    }
+
+    #[test]
+    fn loads_config_from_cargo_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("Cargo.toml");
+        std::fs::write(
+            &path,
+            "[workspace]\nmembers = []\n\n[workspace.metadata.cc-check]\nextra_types = [\"wip\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(Config::from_file(&path).unwrap().extra_types, vec!["wip"]);
+    }
+
+    #[test]
+    fn loads_config_from_package_json_with_locations() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("package.json");
+        std::fs::write(
+            &path,
+            "{\n  \"name\": \"x\",\n  \"cc-check\": { \"max_subject\": 40 }\n}\n",
+        )
+        .unwrap();
+        assert_eq!(Config::from_file(&path).unwrap().max_subject, Some(40));
+
+        std::fs::write(
+            &path,
+            "{\n  \"cc-check\": {\n    \"max_subjet\": 40\n  }\n}\n",
+        )
+        .unwrap();
+        let err = Config::from_file(&path).unwrap_err();
+        let err = err.downcast_ref::<ConfigError>().unwrap();
+        assert_eq!(err.line, 3);
+        assert_eq!(err.suggestion.as_deref(), Some("max_subject"));
+    }
+
+    #[test]
+    fn discovery_skips_host_files_without_section() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        let nested = temp_dir.path().join("crates").join("inner");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("Cargo.toml"), "[package]\nname = \"inner\"").unwrap();
+        std::fs::create_dir(temp_dir.path().join(".config")).unwrap();
+        std::fs::write(
+            temp_dir.path().join(".config").join("cc-check.toml"),
+            "max_subject = 50",
+        )
+        .unwrap();
+
+        let found = Config::find_config_file_from(&nested, &RepoPaths::discover(&nested)).unwrap();
+        assert_eq!(found, temp_dir.path().join(".config").join("cc-check.toml"));
+    }
+
+    #[test]
+    fn discovery_reports_broken_package_json_with_section() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        let path = temp_dir.path().join("package.json");
+        std::fs::write(
+            &path,
+            "{\n  \"name\": \"x\",\n  \"cc-check\": { \"max_subject\": 40, }\n}\n",
+        )
+        .unwrap();
+
+        let found =
+            Config::find_config_file_from(temp_dir.path(), &RepoPaths::discover(temp_dir.path()))
+                .unwrap();
+        assert_eq!(found, path);
+        let err = Config::from_file(&path).unwrap_err();
+        assert_eq!(err.downcast_ref::<ConfigError>().unwrap().line, 3);
+
+        std::fs::write(&path, "{ \"name\": \"x\", }").unwrap();
+        assert!(Config::find_config_file_from(
+            temp_dir.path(),
+            &RepoPaths::discover(temp_dir.path())
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn discovery_prefers_dedicated_file_over_host_files() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        std::fs::write(
+            temp_dir.path().join("pyproject.toml"),
+            "[tool.cc-check]\nmax_subject = 50",
+        )
+        .unwrap();
+        std::fs::write(temp_dir.path().join("cc-check.toml"), "max_subject = 60").unwrap();
+
+        let found =
+            Config::find_config_file_from(temp_dir.path(), &RepoPaths::discover(temp_dir.path()))
+                .unwrap();
+        assert_eq!(found, temp_dir.path().join("cc-check.toml"));
+    }
+
+    #[test]
+    fn config_chain_lists_configs_from_root_down() {
+        let temp_dir = TempDir::new().unwrap();
+        let api = temp_dir.path().join("packages").join("api");
+        std::fs::create_dir_all(&api).unwrap();
+        std::fs::write(temp_dir.path().join(".cc-check.toml"), "max_subject = 72").unwrap();
+        std::fs::write(
+            api.join("package.json"),
+            "{ \"cc-check\": { \"max_subject\": 50 } }",
+        )
+        .unwrap();
+
+        assert_eq!(
+            Config::find_config_chain(temp_dir.path(), Path::new("packages/api")),
+            vec![
+                temp_dir.path().join(".cc-check.toml"),
+                api.join("package.json")
+            ]
+        );
+        assert_eq!(
+            Config::find_config_chain(temp_dir.path(), Path::new("packages")),
+            vec![temp_dir.path().join(".cc-check.toml")]
+        );
+    }
+
+    fn env(vars: &[(&str, &str)]) -> Result<Config> {
+        Config::from_env_vars(
+            vars.iter()
+                .map(|(name, value)| (name.to_string(), value.to_string())),
+        )
+    }
+
+    #[test]
+    fn env_vars_set_config_keys() {
+        let config = env(&[
+            ("CC_CHECK_MAX_SUBJECT", "50"),
+            ("CC_CHECK_EXTRA_TYPES", "wip, release"),
+            ("CC_CHECK_NO_TRAILING_PERIOD", "false"),
+            ("CC_CHECK_PRESET", "eslint"),
+            ("CC_CHECK_TYPE_INFO", "{ wip = { semver = \"none\" } }"),
+            ("CC_CHECK_CONFIG", "ignored.toml"),
+            ("PATH", "/usr/bin"),
+        ])
+        .unwrap();
+
+        assert_eq!(config.max_subject, Some(50));
+        assert_eq!(config.extra_types, vec!["wip", "release"]);
+        assert_eq!(config.no_trailing_period, Some(false));
+        assert_eq!(config.preset.as_deref(), Some("eslint"));
+        assert!(config.type_info.contains_key("wip"));
+    }
+
+    #[test]
+    fn env_vars_reject_unknown_names_and_bad_values() {
+        let err = env(&[("CC_CHECK_MAX_SUBJET", "50")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown environment variable CC_CHECK_MAX_SUBJET (did you mean CC_CHECK_MAX_SUBJECT?)"
+        );
+
+        let err = env(&[("CC_CHECK_MAX_SUBJECT", "long")]).unwrap_err();
+        assert!(err.to_string().contains("CC_CHECK_MAX_SUBJECT"));
+
+        let err = env(&[("CC_CHECK_EXTENDS", "base.toml")]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown environment variable CC_CHECK_EXTENDS"
+        );
+    }
+
+    #[test]
+    fn env_layer_sits_between_files_and_cli() {
+        let mut resolved = ResolvedConfig::new();
+        let file: Config = toml::from_str("max_subject = 50\nignore_comments = false").unwrap();
+        resolved
+            .push(Origin::File(PathBuf::from("a.toml")), &file)
+            .unwrap();
+        resolved
+            .push(
+                Origin::Env,
+                &env(&[("CC_CHECK_MAX_SUBJECT", "60")]).unwrap(),
+            )
+            .unwrap();
+        resolved
+            .push(
+                Origin::Cli,
+                &Config {
+                    ignore_comments: Some(true),
+                    ..Config::default()
+                },
+            )
+            .unwrap();
+
+        let entries = resolved.entries().unwrap();
+        let max = entries.iter().find(|e| e.key == "max_subject").unwrap();
+        assert_eq!(max.value, Value::Integer(60));
+        assert_eq!(max.origin.describe(&max.key), "env CC_CHECK_MAX_SUBJECT");
+        let comments = entries.iter().find(|e| e.key == "ignore_comments").unwrap();
+        assert_eq!(comments.origin, Origin::Cli);
+    }
+
     #[test]
     fn returns_error_for_invalid_toml() {
         let temp_dir = TempDir::new().unwrap();
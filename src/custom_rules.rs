@@ -0,0 +1,375 @@
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::content::Severity;
+use crate::message::Message;
+use crate::ParsedHeader;
+
+/// Part of a commit message a custom rule looks at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Target {
+    Header,
+    Type,
+    Scope,
+    Subject,
+    Body,
+    /// Values of the footers with this token, e.g. `footer:Refs`
+    Footer(String),
+    /// The whole cleaned-up message
+    Message,
+}
+
+impl TryFrom<String> for Target {
+    type Error = String;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "header" => Ok(Target::Header),
+            "type" => Ok(Target::Type),
+            "scope" => Ok(Target::Scope),
+            "subject" => Ok(Target::Subject),
+            "body" => Ok(Target::Body),
+            "message" => Ok(Target::Message),
+            _ => match value.strip_prefix("footer:") {
+                Some(token) if !token.trim().is_empty() => {
+                    Ok(Target::Footer(token.trim().to_string()))
+                }
+                _ => Err(format!(
+                    "unknown target `{value}` (expected header, type, scope, subject, body, footer:<token> or message)"
+                )),
+            },
+        }
+    }
+}
+
+impl From<Target> for String {
+    fn from(target: Target) -> Self {
+        target.to_string()
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Target::Header => f.write_str("header"),
+            Target::Type => f.write_str("type"),
+            Target::Scope => f.write_str("scope"),
+            Target::Subject => f.write_str("subject"),
+            Target::Body => f.write_str("body"),
+            Target::Footer(token) => write!(f, "footer:{token}"),
+            Target::Message => f.write_str("message"),
+        }
+    }
+}
+
+/// When a custom rule applies; every condition given must hold
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Condition {
+    /// Commit types the rule applies to
+    #[serde(rename = "type", skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+
+    /// Scopes the rule applies to
+    #[serde(rename = "scope", skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+
+    /// Only breaking (true) or only non-breaking (false) commits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breaking: Option<bool>,
+}
+
+/// A house rule, configured as a `[[custom_rules]]` entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CustomRuleConfig {
+    /// Name reported with violations
+    pub id: String,
+
+    /// header, type, scope, subject, body, footer:<token> or message
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<Target>,
+
+    /// Regex the target must match (a missing scope or footer counts as "")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+
+    /// Regex the target must not match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forbid_pattern: Option<String>,
+
+    /// Only apply to matching commits
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<Condition>,
+
+    /// "error" (default) or "warning"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+
+    /// Message reported when the rule is broken
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// A compiled custom rule
+#[derive(Debug, Clone)]
+pub struct CustomRule {
+    pub id: String,
+    pub target: Target,
+    pub pattern: Option<Regex>,
+    pub forbid_pattern: Option<Regex>,
+    pub when: Condition,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Compile the `[[custom_rules]]` entries
+pub fn compile(configs: &[CustomRuleConfig]) -> Result<Vec<CustomRule>> {
+    let mut rules: Vec<CustomRule> = Vec::new();
+    for config in configs {
+        let id = config.id.trim();
+        if id.is_empty() {
+            bail!("custom_rules entries need an `id`");
+        }
+        if rules.iter().any(|rule| rule.id == id) {
+            bail!("duplicate custom rule id `{id}`");
+        }
+        let Some(target) = config.target.clone() else {
+            bail!("custom rule `{id}` needs a `target`");
+        };
+        if config.pattern.is_none() && config.forbid_pattern.is_none() {
+            bail!("custom rule `{id}` needs a `pattern` or `forbid_pattern`");
+        }
+        let compile = |pattern: &Option<String>, key: &str| {
+            pattern
+                .as_deref()
+                .map(|source| {
+                    Regex::new(source)
+                        .with_context(|| format!("invalid {key} `{source}` in custom rule `{id}`"))
+                })
+                .transpose()
+        };
+
+        rules.push(CustomRule {
+            id: id.to_string(),
+            pattern: compile(&config.pattern, "pattern")?,
+            forbid_pattern: compile(&config.forbid_pattern, "forbid_pattern")?,
+            message: config
+                .message
+                .clone()
+                .unwrap_or_else(|| format!("{target} breaks custom rule `{id}`")),
+            target,
+            when: config.when.clone().unwrap_or_default(),
+            severity: config.severity.unwrap_or_default(),
+        });
+    }
+    Ok(rules)
+}
+
+/// A broken custom rule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// Id of the rule
+    pub rule: String,
+    pub message: String,
+    pub severity: Severity,
+    /// What the rule looked at
+    pub target: Target,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]", self.message, self.rule)
+    }
+}
+
+/// `violations` joined for an error message
+pub fn describe(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl CustomRule {
+    /// Whether the rule applies to a commit with this header and message
+    fn applies(&self, header: &ParsedHeader, message: &Message) -> bool {
+        let when = &self.when;
+        let breaking = header.breaking
+            || message
+                .footers
+                .iter()
+                .any(|footer| footer.token.starts_with("BREAKING"));
+        (when.types.is_empty() || when.types.iter().any(|t| t == header.commit_type))
+            && (when.scopes.is_empty()
+                || header
+                    .scope
+                    .is_some_and(|scope| when.scopes.iter().any(|s| s == scope)))
+            && when.breaking.is_none_or(|wanted| wanted == breaking)
+    }
+
+    /// The texts the target refers to; a missing scope or footer is a single ""
+    fn texts<'a>(
+        &self,
+        header: &ParsedHeader<'a>,
+        message: &'a Message,
+        raw: &'a str,
+    ) -> Vec<&'a str> {
+        match &self.target {
+            Target::Header => vec![message.header.as_str()],
+            Target::Type => vec![header.commit_type],
+            Target::Scope => vec![header.scope.unwrap_or_default()],
+            Target::Subject => vec![header.subject],
+            Target::Body => vec![message.body.as_str()],
+            Target::Footer(token) => {
+                let values: Vec<&str> = message
+                    .footers
+                    .iter()
+                    .filter(|footer| footer.token.eq_ignore_ascii_case(token))
+                    .map(|footer| footer.value.as_str())
+                    .collect();
+                if values.is_empty() {
+                    vec![""]
+                } else {
+                    values
+                }
+            }
+            Target::Message => vec![raw.trim_end()],
+        }
+    }
+
+    /// Check the rule against a parsed commit; `raw` is the cleaned-up message
+    pub fn check(&self, header: &ParsedHeader, message: &Message, raw: &str) -> Option<Violation> {
+        if !self.applies(header, message) {
+            return None;
+        }
+        let broken = self.texts(header, message, raw).into_iter().any(|text| {
+            self.pattern.as_ref().is_some_and(|p| !p.is_match(text))
+                || self
+                    .forbid_pattern
+                    .as_ref()
+                    .is_some_and(|p| p.is_match(text))
+        });
+        broken.then(|| Violation {
+            rule: self.id.clone(),
+            message: self.message.clone(),
+            severity: self.severity,
+            target: self.target.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{message, HeaderRules};
+
+    fn violations(configs: &[CustomRuleConfig], text: &str) -> Vec<String> {
+        let rules = compile(configs).unwrap();
+        let header_rules = HeaderRules::new(&[], 0, false);
+        let parsed_message = message::parse(text);
+        let header = header_rules.parse(&parsed_message.header).unwrap();
+        rules
+            .iter()
+            .filter_map(|rule| rule.check(&header, &parsed_message, text))
+            .map(|violation| violation.rule)
+            .collect()
+    }
+
+    fn rule(id: &str, target: &str) -> CustomRuleConfig {
+        CustomRuleConfig {
+            id: id.to_string(),
+            target: Some(Target::try_from(target.to_string()).unwrap()),
+            ..CustomRuleConfig::default()
+        }
+    }
+
+    #[test]
+    fn parses_targets() {
+        assert_eq!(
+            Target::try_from("footer:Refs".to_string()),
+            Ok(Target::Footer("Refs".to_string()))
+        );
+        assert_eq!(
+            Target::Footer("Refs".to_string()).to_string(),
+            "footer:Refs"
+        );
+        assert!(Target::try_from("footer:".to_string()).is_err());
+        assert!(Target::try_from("trailer".to_string()).is_err());
+    }
+
+    #[test]
+    fn pattern_and_forbid_pattern() {
+        let configs = [
+            CustomRuleConfig {
+                pattern: Some(r"^(api|web)$".to_string()),
+                ..rule("known-scope", "scope")
+            },
+            CustomRuleConfig {
+                forbid_pattern: Some(r"(?i)\bhotfix\b".to_string()),
+                ..rule("no-hotfix", "subject")
+            },
+        ];
+        assert!(violations(&configs, "feat(api): add x\n").is_empty());
+        assert_eq!(
+            violations(&configs, "feat: hotfix x\n"),
+            vec!["known-scope", "no-hotfix"]
+        );
+    }
+
+    #[test]
+    fn conditions_and_footer_targets() {
+        let configs = [CustomRuleConfig {
+            pattern: Some(r"^#\d+$".to_string()),
+            when: Some(Condition {
+                types: vec!["fix".to_string()],
+                ..Condition::default()
+            }),
+            ..rule("fix-refs", "footer:Refs")
+        }];
+        assert!(violations(&configs, "feat: x\n").is_empty());
+        assert_eq!(violations(&configs, "fix: x\n"), vec!["fix-refs"]);
+        assert!(violations(&configs, "fix: x\n\nRefs: #12\n").is_empty());
+        assert_eq!(
+            violations(&configs, "fix: x\n\nRefs: #12\nRefs: PROJ-1\n"),
+            vec!["fix-refs"]
+        );
+    }
+
+    #[test]
+    fn breaking_condition() {
+        let configs = [CustomRuleConfig {
+            pattern: Some(r"(?s).{20,}".to_string()),
+            when: Some(Condition {
+                breaking: Some(true),
+                ..Condition::default()
+            }),
+            ..rule("explain-breaking", "body")
+        }];
+        assert!(violations(&configs, "feat: x\n").is_empty());
+        assert_eq!(violations(&configs, "feat!: x\n"), vec!["explain-breaking"]);
+        assert_eq!(
+            violations(&configs, "feat: x\n\nBREAKING CHANGE: y\n"),
+            vec!["explain-breaking"]
+        );
+    }
+
+    #[test]
+    fn invalid_rules_are_rejected() {
+        assert!(compile(&[rule("no-pattern", "subject")]).is_err());
+        let with_pattern = CustomRuleConfig {
+            pattern: Some(".".to_string()),
+            ..rule("dup", "subject")
+        };
+        assert!(compile(&[with_pattern.clone(), with_pattern]).is_err());
+        let no_target = CustomRuleConfig {
+            target: None,
+            pattern: Some(".".to_string()),
+            ..rule("x", "subject")
+        };
+        assert!(compile(&[no_target]).is_err());
+    }
+}
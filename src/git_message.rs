@@ -0,0 +1,178 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// A message format git (or a forge) generates itself, allowed through without
+/// conventional-commit validation when enabled in `git_messages`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GitMessageKind {
+    /// `Merge branch 'x' into y`, `Merge branches 'a' and 'b'`
+    MergeBranch,
+    /// `Merge tag 'v1.0'`
+    MergeTag,
+    /// `Merge remote-tracking branch 'origin/x'`
+    MergeRemoteTracking,
+    /// `Merge commit '<sha>'`
+    MergeCommit,
+    /// `Merge pull request #12 from owner/branch`
+    MergePullRequest,
+    /// `Revert "<header>"` with `This reverts commit <sha>.` in the body
+    Revert,
+}
+
+impl GitMessageKind {
+    /// Every kind, the default for `git_messages`
+    pub const ALL: &'static [GitMessageKind] = &[
+        GitMessageKind::MergeBranch,
+        GitMessageKind::MergeTag,
+        GitMessageKind::MergeRemoteTracking,
+        GitMessageKind::MergeCommit,
+        GitMessageKind::MergePullRequest,
+        GitMessageKind::Revert,
+    ];
+
+    fn header_pattern(self) -> &'static str {
+        // `into` targets are quoted by GitLab and bare in git's own messages
+        match self {
+            GitMessageKind::MergeBranch => {
+                r"^Merge (?:branch '[^']+'|branches '[^']+'(?:, '[^']+')* and '[^']+')(?: of \S+)?(?: into (?:'[^']+'|\S+))?$"
+            }
+            GitMessageKind::MergeTag => {
+                r"^Merge tag '[^']+'(?: of \S+)?(?: into (?:'[^']+'|\S+))?$"
+            }
+            GitMessageKind::MergeRemoteTracking => {
+                r"^Merge remote-tracking branch '[^']+'(?: into (?:'[^']+'|\S+))?$"
+            }
+            GitMessageKind::MergeCommit => {
+                r"^Merge commit '[0-9a-f]{7,40}'(?: into (?:'[^']+'|\S+))?$"
+            }
+            GitMessageKind::MergePullRequest => r"^Merge pull request #\d+ from \S+$",
+            GitMessageKind::Revert => r#"^Revert ".+"$"#,
+        }
+    }
+}
+
+/// Compiled header patterns, in the order of [`GitMessageKind::ALL`]
+fn header_patterns() -> &'static [(GitMessageKind, Regex)] {
+    static PATTERNS: OnceLock<Vec<(GitMessageKind, Regex)>> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        GitMessageKind::ALL
+            .iter()
+            .map(|kind| {
+                (
+                    *kind,
+                    Regex::new(kind.header_pattern()).expect("valid regex"),
+                )
+            })
+            .collect()
+    })
+}
+
+/// The commit hash in git's `This reverts commit <sha>.` body line, if `line` is one
+pub fn reverted_commit(line: &str) -> Option<&str> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN
+        .get_or_init(|| {
+            Regex::new(r"^This reverts commit ([0-9a-f]{7,40})(?:\.|, reversing)")
+                .expect("valid regex")
+        })
+        .captures(line.trim())
+        .and_then(|captures| captures.get(1))
+        .map(|sha| sha.as_str())
+}
+
+/// Recognise a message git generated, given its header and full text; a `Revert`
+/// header only counts when the body names the reverted commit
+pub fn classify(header: &str, message: &str) -> Option<GitMessageKind> {
+    let kind = header_patterns()
+        .iter()
+        .find(|(_, pattern)| pattern.is_match(header))
+        .map(|(kind, _)| *kind)?;
+
+    if kind == GitMessageKind::Revert
+        && !message.lines().any(|line| reverted_commit(line).is_some())
+    {
+        return None;
+    }
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_kind(header: &str) -> Option<GitMessageKind> {
+        classify(header, header)
+    }
+
+    #[test]
+    fn recognises_merge_forms() {
+        let cases = [
+            ("Merge branch 'feature/x'", GitMessageKind::MergeBranch),
+            ("Merge branch 'x' into main", GitMessageKind::MergeBranch),
+            ("Merge branch 'x' into 'main'", GitMessageKind::MergeBranch),
+            (
+                "Merge branch 'x' of github.com:o/r into y",
+                GitMessageKind::MergeBranch,
+            ),
+            (
+                "Merge branches 'a', 'b' and 'c'",
+                GitMessageKind::MergeBranch,
+            ),
+            ("Merge tag 'v1.2.0'", GitMessageKind::MergeTag),
+            (
+                "Merge remote-tracking branch 'origin/main' into x",
+                GitMessageKind::MergeRemoteTracking,
+            ),
+            ("Merge commit '1a2b3c4d'", GitMessageKind::MergeCommit),
+            (
+                "Merge pull request #123 from user/feature",
+                GitMessageKind::MergePullRequest,
+            ),
+        ];
+        for (header, kind) in cases {
+            assert_eq!(header_kind(header), Some(kind), "{header}");
+        }
+    }
+
+    #[test]
+    fn rejects_merge_lookalikes() {
+        for header in [
+            "Merge stuff I did",
+            "Merge branch feature",
+            "Merge pull request from someone",
+            "Merged branch 'x'",
+        ] {
+            assert_eq!(header_kind(header), None, "{header}");
+        }
+    }
+
+    #[test]
+    fn revert_requires_reverted_commit_in_body() {
+        let header = "Revert \"feat: add x\"";
+        assert_eq!(classify(header, header), None);
+        assert_eq!(
+            classify(
+                header,
+                "Revert \"feat: add x\"\n\nThis reverts commit 1a2b3c4d5e6f.\n"
+            ),
+            Some(GitMessageKind::Revert)
+        );
+        assert_eq!(classify("Revert y", "This reverts commit 1a2b3c4."), None);
+    }
+
+    #[test]
+    fn finds_reverted_commit_in_git_line() {
+        assert_eq!(
+            reverted_commit("  This reverts commit 1a2b3c4d5e6f."),
+            Some("1a2b3c4d5e6f")
+        );
+        assert_eq!(
+            reverted_commit("This reverts commit 1a2b3c4, reversing"),
+            Some("1a2b3c4")
+        );
+        assert_eq!(reverted_commit("this reverts commit 1a2b3c4."), None);
+        assert_eq!(reverted_commit("This reverts commit 1a2b3c4d5e6fzz."), None);
+    }
+}
@@ -1,8 +1,39 @@
 use anyhow::{bail, Result};
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use unicode_width::UnicodeWidthStr;
 
+pub mod branch;
+pub mod cleanup;
+pub mod commitlint;
 pub mod config;
+pub mod content;
+pub mod custom_rules;
+pub mod git_message;
+pub mod message;
+pub mod plugin;
+pub mod preset;
+pub mod references;
+pub mod repo;
+pub mod revert;
+pub mod script;
+pub mod signoff;
+pub mod skip;
+pub mod trailers;
+pub mod types;
+
+/// Default allowed types follow the Angular convention (widely adopted with Conventional Commits).
+/// This includes all 11 standard types: feat, fix, docs, style, refactor, perf, test, build, ci, chore, revert.
+/// Reference: https://www.conventionalcommits.org/
+/// Reference: https://github.com/angular/angular/blob/main/CONTRIBUTING.md#type
+pub const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "chore", "docs", "style", "refactor", "perf", "test", "build", "ci", "revert",
+];
+
+/// Default header pattern: `<type>(<scope>)!: <subject>`
+pub const DEFAULT_HEADER_PATTERN: &str =
+    r"^(?P<type>[a-z]+)(?P<scope>\([^)]+\))?(?P<bang>!)?: (?P<subject>.+)$";
 
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
@@ -16,10 +47,268 @@ pub enum ValidationError {
     DisallowedType(String),
     #[error("subject must be non-empty")]
     EmptySubject,
-    #[error("subject exceeds {0} characters ({1})")]
-    SubjectTooLong(usize, usize),
+    #[error("subject exceeds {0} {2} ({1})")]
+    SubjectTooLong(usize, usize, LengthUnit),
+    #[error("subject must be at least {0} {2} ({1})")]
+    SubjectTooShort(usize, usize, LengthUnit),
+    #[error("header exceeds {0} {2} ({1})")]
+    HeaderTooLong(usize, usize, LengthUnit),
     #[error("subject must not end with a period")]
     TrailingPeriod,
+    #[error("subject must {}", .0.requirement())]
+    SubjectCase(SubjectCase),
+    #[error(
+        "use a conventional `revert: <header>` commit instead of git's default Revert message"
+    )]
+    GitRevert,
+    #[error("revert commits must reference the reverted commit (e.g. `Refs: <sha>`)")]
+    MissingRevertRef,
+    #[error("{0} commits must reference an issue (e.g. `PROJ-123` or `#123`)")]
+    MissingReference(String),
+    #[error("commit type '{found}' does not match branch '{branch}' (expected '{expected}')")]
+    BranchTypeMismatch {
+        branch: String,
+        expected: String,
+        found: String,
+    },
+    #[error("commit must reference {ticket} from branch '{branch}' (e.g. add `Refs: {ticket}`)")]
+    MissingBranchTicket { branch: String, ticket: String },
+    #[error(
+        "missing `Signed-off-by: Name <email>` trailer (use `git commit -s` or `cc-check check --fix`)"
+    )]
+    MissingSignoff,
+    #[error("invalid sign-off `{0}` (expected `Name <email>`)")]
+    InvalidSignoff(String),
+    #[error("commit must be signed off by its author {0}")]
+    SignoffMismatch(String),
+    #[error("{}", content::describe(.0))]
+    ForbiddenContent(Vec<content::ContentMatch>),
+    #[error("{}", custom_rules::describe(.0))]
+    CustomRule(Vec<custom_rules::Violation>),
+    #[error("plugin `{0}` failed: {1}")]
+    PluginFailed(String, String),
+    #[error("rule script `{0}` failed: {1}")]
+    ScriptFailed(String, String),
+    #[error("trailer `{0}` is not in trailers.allowed")]
+    DisallowedTrailer(String),
+    #[error("missing required `{0}:` trailer")]
+    MissingTrailer(String),
+    #[error("invalid `{token}` trailer `{value}` (expected {expected})")]
+    InvalidTrailer {
+        token: String,
+        value: String,
+        expected: String,
+    },
+    #[error("`{0}` trailers must come before `{1}` trailers")]
+    TrailerOrder(String, String),
+    #[error("duplicate `{0}` trailer")]
+    DuplicateTrailer(String),
+    #[error("co-author {0} is not listed in .mailmap")]
+    UnknownCoAuthor(String),
+    #[error("reverted commit {0} not found in history")]
+    UnknownRevertedCommit(String),
+    #[error("revert subject does not match the header of reverted commit {0} (`{1}`)")]
+    RevertSubjectMismatch(String, String),
+    #[error("unsquashed {0} commit; run `git rebase -i --autosquash` before merging")]
+    UnsquashedCommit(Autosquash),
+}
+
+/// Kind of commit created by `git commit --fixup`/`--squash`, folded into its target
+/// by `git rebase --autosquash`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Autosquash {
+    Fixup,
+    Squash,
+    Amend,
+}
+
+impl Autosquash {
+    /// The header prefix git uses, e.g. `fixup!`
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Autosquash::Fixup => "fixup!",
+            Autosquash::Squash => "squash!",
+            Autosquash::Amend => "amend!",
+        }
+    }
+}
+
+impl std::fmt::Display for Autosquash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.prefix())
+    }
+}
+
+/// Split an autosquash header such as `fixup! feat: add x` into its kind and the
+/// header of the commit it targets; repeated prefixes (`fixup! fixup! ...`) are
+/// removed, and the outermost kind is returned
+pub fn strip_autosquash_prefix(header: &str) -> Option<(Autosquash, &str)> {
+    let mut kind = None;
+    let mut rest = header;
+    loop {
+        let found = [Autosquash::Fixup, Autosquash::Squash, Autosquash::Amend]
+            .into_iter()
+            .find_map(|k| rest.strip_prefix(k.prefix()).map(|r| (k, r)));
+        match found {
+            Some((k, r)) => {
+                kind.get_or_insert(k);
+                rest = r.trim_start();
+            }
+            None => break,
+        }
+    }
+    kind.map(|kind| (kind, rest))
+}
+
+/// Whether an autosquash target is a commit hash (`git commit --fixup` with a message
+/// that names the commit by hash) rather than a header
+pub fn is_commit_hash(target: &str) -> bool {
+    (7..=40).contains(&target.len()) && target.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Case rule for the first letter of the subject
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubjectCase {
+    /// No restriction
+    #[default]
+    Any,
+    /// Must not start with an uppercase letter
+    Lower,
+    /// Must start with an uppercase letter
+    Sentence,
+}
+
+impl SubjectCase {
+    fn requirement(self) -> &'static str {
+        match self {
+            SubjectCase::Any => "be any case",
+            SubjectCase::Lower => "not start with an uppercase letter",
+            SubjectCase::Sentence => "start with an uppercase letter",
+        }
+    }
+
+    fn matches(self, subject: &str) -> bool {
+        let first = subject.chars().next();
+        match self {
+            SubjectCase::Any => true,
+            SubjectCase::Lower => !first.is_some_and(char::is_uppercase),
+            SubjectCase::Sentence => first.is_some_and(char::is_uppercase),
+        }
+    }
+}
+
+/// How header and subject lengths are measured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LengthUnit {
+    /// Unicode scalar values, so every character counts as one
+    #[default]
+    Chars,
+    /// Terminal display columns: East Asian wide characters and emoji count as two
+    Columns,
+}
+
+impl LengthUnit {
+    /// Length of `text` in this unit
+    pub fn measure(self, text: &str) -> usize {
+        match self {
+            LengthUnit::Chars => text.chars().count(),
+            LengthUnit::Columns => text.width(),
+        }
+    }
+}
+
+impl std::fmt::Display for LengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LengthUnit::Chars => "characters",
+            LengthUnit::Columns => "columns",
+        })
+    }
+}
+
+impl std::str::FromStr for LengthUnit {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "chars" => Ok(LengthUnit::Chars),
+            "columns" => Ok(LengthUnit::Columns),
+            _ => Err("supported units: chars, columns".to_string()),
+        }
+    }
+}
+
+/// The parts of a header matched by the header pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedHeader<'a> {
+    /// Commit type, e.g. `feat`
+    pub commit_type: &'a str,
+    /// Scope without surrounding parentheses, if any
+    pub scope: Option<&'a str>,
+    /// Whether the header carries the `!` breaking-change marker
+    pub breaking: bool,
+    /// Subject, trimmed
+    pub subject: &'a str,
+}
+
+/// Rules a commit header is validated against
+#[derive(Debug, Clone)]
+pub struct HeaderRules {
+    /// Header pattern with named groups `type`, `subject` and optionally `scope`
+    pub pattern: Regex,
+    /// Allowed types; empty allows any type
+    pub allowed_types: Vec<String>,
+    /// Types that are rejected even when `allowed_types` would accept them
+    pub disallowed_types: Vec<String>,
+    /// Maximum subject length (0 to disable)
+    pub max_subject: usize,
+    /// Minimum subject length (0 to disable)
+    pub min_subject: usize,
+    /// Maximum length of the whole header line (0 to disable)
+    pub max_header: usize,
+    /// How `max_subject`, `min_subject` and `max_header` are measured
+    pub length_unit: LengthUnit,
+    /// Disallow trailing period in subject
+    pub no_trailing_period: bool,
+    /// Case rule for the subject
+    pub subject_case: SubjectCase,
+}
+
+impl HeaderRules {
+    /// Split `header` into its parts, or `None` if it does not match the pattern
+    pub fn parse<'a>(&self, header: &'a str) -> Option<ParsedHeader<'a>> {
+        let captures = self.pattern.captures(header)?;
+        let group = |name: &str| captures.name(name).map(|m| m.as_str());
+        let scope = group("scope").map(|scope| {
+            scope
+                .strip_prefix('(')
+                .and_then(|s| s.strip_suffix(')'))
+                .unwrap_or(scope)
+        });
+
+        Some(ParsedHeader {
+            commit_type: group("type").unwrap_or(""),
+            scope,
+            breaking: group("bang").is_some(),
+            subject: group("subject").unwrap_or("").trim(),
+        })
+    }
+
+    /// Rules using the default header pattern and no case restriction
+    pub fn new(allowed_types: &[String], max_subject: usize, no_trailing_period: bool) -> Self {
+        Self {
+            pattern: Regex::new(DEFAULT_HEADER_PATTERN).expect("valid regex"),
+            allowed_types: allowed_types.to_vec(),
+            disallowed_types: Vec::new(),
+            max_subject,
+            min_subject: 0,
+            max_header: 0,
+            length_unit: LengthUnit::Chars,
+            no_trailing_period,
+            subject_case: SubjectCase::Any,
+        }
+    }
 }
 
 /// Extract the first meaningful line from a commit message, skipping comment lines and empties.
@@ -37,32 +326,30 @@ pub fn first_meaningful_line(message: &str, ignore_comments: bool) -> Option<Str
     None
 }
 
-/// Return true if the message is a merge commit header and should be optionally allowed.
-pub fn is_merge_like_header(line: &str) -> bool {
-    line.starts_with("Merge ") || line.starts_with("Revert ")
-}
-
 pub fn validate_header(
     header_line: &str,
     allowed_types: &[String],
     max_subject_len: usize,
     no_trailing_period: bool,
 ) -> Result<(), ValidationError> {
-    let header_re =
-        Regex::new(r"^(?P<type>[a-z]+)(?P<scope>\([^)]+\))?(?P<bang>!)?: (?P<subject>.+)$")
-            .expect("valid regex");
-
-    let captures = header_re
-        .captures(header_line)
-        .ok_or(ValidationError::BadHeader)?;
-    let commit_type = captures.name("type").map(|m| m.as_str()).unwrap_or("");
-    let subject = captures
-        .name("subject")
-        .map(|m| m.as_str())
-        .unwrap_or("")
-        .trim();
-
-    if !allowed_types.iter().any(|t| t == commit_type) {
+    validate_header_with_rules(
+        header_line,
+        &HeaderRules::new(allowed_types, max_subject_len, no_trailing_period),
+    )
+}
+
+/// Validate a header against a full set of rules (pattern, types, subject limits and case)
+pub fn validate_header_with_rules(
+    header_line: &str,
+    rules: &HeaderRules,
+) -> Result<(), ValidationError> {
+    let parsed = rules.parse(header_line).ok_or(ValidationError::BadHeader)?;
+    let commit_type = parsed.commit_type;
+    let subject = parsed.subject;
+
+    let allowed =
+        rules.allowed_types.is_empty() || rules.allowed_types.iter().any(|t| t == commit_type);
+    if !allowed || rules.disallowed_types.iter().any(|t| t == commit_type) {
         return Err(ValidationError::DisallowedType(commit_type.to_string()));
     }
 
@@ -70,22 +357,58 @@ pub fn validate_header(
         return Err(ValidationError::EmptySubject);
     }
 
-    if max_subject_len > 0 && subject.chars().count() > max_subject_len {
+    let unit = rules.length_unit;
+    let subject_len = unit.measure(subject);
+    if rules.max_subject > 0 && subject_len > rules.max_subject {
         return Err(ValidationError::SubjectTooLong(
-            max_subject_len,
-            subject.chars().count(),
+            rules.max_subject,
+            subject_len,
+            unit,
+        ));
+    }
+    if subject_len < rules.min_subject {
+        return Err(ValidationError::SubjectTooShort(
+            rules.min_subject,
+            subject_len,
+            unit,
+        ));
+    }
+    let header_len = unit.measure(header_line.trim());
+    if rules.max_header > 0 && header_len > rules.max_header {
+        return Err(ValidationError::HeaderTooLong(
+            rules.max_header,
+            header_len,
+            unit,
         ));
     }
 
-    if no_trailing_period && subject.ends_with('.') {
+    if rules.no_trailing_period && subject.ends_with('.') {
         return Err(ValidationError::TrailingPeriod);
     }
 
+    if !rules.subject_case.matches(subject) {
+        return Err(ValidationError::SubjectCase(rules.subject_case));
+    }
+
     Ok(())
 }
 
-/// Find the repository root by looking for Cargo.toml or .git directory
+/// Find the repository root: the git working tree root if there is one (asking git, so
+/// worktrees and submodules work), otherwise the nearest package root (e.g. Cargo.toml)
 pub fn find_repo_root() -> Result<PathBuf> {
+    let current_dir = std::env::current_dir()?;
+    let paths = repo::RepoPaths::discover(&current_dir);
+
+    match paths.git_root.or(paths.package_root) {
+        Some(root) => Ok(root),
+        None => bail!("could not find repository root (no Cargo.toml or .git found)"),
+    }
+}
+
+/// Find the nearest directory holding a `Cargo.toml` (or, failing that, a `.git`), where
+/// a locally built `target/` lives; unlike [`find_repo_root`] this stays inside a crate
+/// nested in a larger repository
+pub fn find_crate_root() -> Result<PathBuf> {
     let current_dir = std::env::current_dir()?;
     let mut dir = current_dir.as_path();
 
@@ -96,7 +419,7 @@ pub fn find_repo_root() -> Result<PathBuf> {
 
         match dir.parent() {
             Some(parent) => dir = parent,
-            None => bail!("could not find repository root (no Cargo.toml or .git found)"),
+            None => bail!("could not find crate root (no Cargo.toml or .git found)"),
         }
     }
 }
@@ -193,7 +516,122 @@ mod tests {
             let long_subject = "a".repeat(80);
             let header = format!("feat: {}", long_subject);
             let err = validate_header(&header, &allowed(), 72, true).unwrap_err();
-            assert!(matches!(err, ValidationError::SubjectTooLong(72, 80)));
+            assert!(matches!(
+                err,
+                ValidationError::SubjectTooLong(72, 80, LengthUnit::Chars)
+            ));
+        }
+    }
+
+    mod header_rules {
+        use super::*;
+
+        fn rules(pattern: &str, types: &[&str]) -> HeaderRules {
+            HeaderRules {
+                pattern: Regex::new(pattern).unwrap(),
+                allowed_types: types.iter().map(|t| t.to_string()).collect(),
+                disallowed_types: Vec::new(),
+                max_subject: 72,
+                min_subject: 0,
+                max_header: 0,
+                length_unit: LengthUnit::Chars,
+                no_trailing_period: false,
+                subject_case: SubjectCase::Any,
+            }
+        }
+
+        #[test]
+        fn parse_splits_header_parts() {
+            let rules = rules(DEFAULT_HEADER_PATTERN, &[]);
+            assert_eq!(
+                rules.parse("feat(api)!: drop v1"),
+                Some(ParsedHeader {
+                    commit_type: "feat",
+                    scope: Some("api"),
+                    breaking: true,
+                    subject: "drop v1",
+                })
+            );
+            assert_eq!(rules.parse("not a header"), None);
+        }
+
+        #[test]
+        fn custom_pattern_extracts_type() {
+            let rules = rules(
+                r"^\[(?P<type>[A-Z]+)(?: (?P<scope>[^\]]+))?\] (?P<subject>.+)$",
+                &["BUGFIX", "FEATURE"],
+            );
+            assert!(validate_header_with_rules("[BUGFIX beta] fix render", &rules).is_ok());
+            assert!(matches!(
+                validate_header_with_rules("[DOC] update", &rules),
+                Err(ValidationError::DisallowedType(t)) if t == "DOC"
+            ));
+            assert!(matches!(
+                validate_header_with_rules("feat: x", &rules),
+                Err(ValidationError::BadHeader)
+            ));
+        }
+
+        #[test]
+        fn empty_type_list_allows_any_type() {
+            let rules = rules(r"^(?P<type>[^:]+): (?P<subject>.+)$", &[]);
+            assert!(validate_header_with_rules("Ajax: Fix timeout handling", &rules).is_ok());
+        }
+
+        #[test]
+        fn disallowed_types_win_over_allowed_types() {
+            let mut rules = rules(DEFAULT_HEADER_PATTERN, &["feat", "chore"]);
+            rules.disallowed_types = vec!["chore".to_string()];
+            assert!(validate_header_with_rules("feat: add x", &rules).is_ok());
+            assert!(matches!(
+                validate_header_with_rules("chore: bump", &rules),
+                Err(ValidationError::DisallowedType(t)) if t == "chore"
+            ));
+        }
+
+        #[test]
+        fn enforces_subject_case() {
+            let mut rules = rules(DEFAULT_HEADER_PATTERN, &["feat"]);
+            rules.subject_case = SubjectCase::Lower;
+            assert!(validate_header_with_rules("feat: add x", &rules).is_ok());
+            assert!(matches!(
+                validate_header_with_rules("feat: Add x", &rules),
+                Err(ValidationError::SubjectCase(SubjectCase::Lower))
+            ));
+
+            rules.subject_case = SubjectCase::Sentence;
+            assert!(validate_header_with_rules("feat: Add x", &rules).is_ok());
+            assert!(validate_header_with_rules("feat: add x", &rules).is_err());
+        }
+
+        #[test]
+        fn enforces_header_and_min_subject_lengths() {
+            let mut rules = rules(DEFAULT_HEADER_PATTERN, &["feat"]);
+            rules.max_header = 20;
+            rules.min_subject = 5;
+            assert!(validate_header_with_rules("feat(ui): add menu", &rules).is_ok());
+            assert!(matches!(
+                validate_header_with_rules("feat(ui): add a menu bar", &rules),
+                Err(ValidationError::HeaderTooLong(20, 24, LengthUnit::Chars))
+            ));
+            assert!(matches!(
+                validate_header_with_rules("feat: add", &rules),
+                Err(ValidationError::SubjectTooShort(5, 3, LengthUnit::Chars))
+            ));
+        }
+
+        #[test]
+        fn measures_display_columns() {
+            assert_eq!(LengthUnit::Chars.measure("添加菜单"), 4);
+            assert_eq!(LengthUnit::Columns.measure("添加菜单"), 8);
+            assert_eq!(LengthUnit::Columns.measure("add 🚀"), 6);
+
+            let mut rules = rules(DEFAULT_HEADER_PATTERN, &["feat"]);
+            rules.max_subject = 6;
+            assert!(validate_header_with_rules("feat: 添加菜单", &rules).is_ok());
+            rules.length_unit = LengthUnit::Columns;
+            let err = validate_header_with_rules("feat: 添加菜单", &rules).unwrap_err();
+            assert_eq!(err.to_string(), "subject exceeds 6 columns (8)");
         }
     }
 
@@ -210,14 +648,44 @@ mod tests {
         }
     }
 
+    mod autosquash {
+        use super::*;
+
+        #[test]
+        fn strips_autosquash_prefixes() {
+            assert_eq!(
+                strip_autosquash_prefix("fixup! feat: add x"),
+                Some((Autosquash::Fixup, "feat: add x"))
+            );
+            assert_eq!(
+                strip_autosquash_prefix("squash! fixup! fix: y"),
+                Some((Autosquash::Squash, "fix: y"))
+            );
+            assert_eq!(
+                strip_autosquash_prefix("amend! docs: z"),
+                Some((Autosquash::Amend, "docs: z"))
+            );
+            assert_eq!(strip_autosquash_prefix("feat: fixup! later"), None);
+        }
+
+        #[test]
+        fn recognises_commit_hash_targets() {
+            assert!(is_commit_hash("1a2b3c4"));
+            assert!(!is_commit_hash("feat: x"));
+            assert!(!is_commit_hash("abc"));
+        }
+    }
+
     mod merge_detection {
         use super::*;
 
         #[test]
         fn merge_like_headers_detected() {
-            assert!(is_merge_like_header("Merge branch 'x'"));
-            assert!(is_merge_like_header("Revert y"));
-            assert!(!is_merge_like_header("feat: x"));
+            use git_message::{classify, GitMessageKind};
+            let merge = "Merge branch 'x'";
+            assert_eq!(classify(merge, merge), Some(GitMessageKind::MergeBranch));
+            assert_eq!(classify("Revert y", "Revert y"), None);
+            assert_eq!(classify("feat: x", "feat: x"), None);
         }
     }
 
@@ -346,6 +814,35 @@ mod tests {
             assert_eq!(actual, expected);
         }
 
+        #[test]
+        fn find_crate_root_stays_in_nested_crate() {
+            use tempfile::TempDir;
+            let original_dir = std::env::current_dir().expect("should get current directory");
+            let _guard = DirGuard {
+                original_dir: original_dir.clone(),
+            };
+            let temp_dir = TempDir::new().expect("should create temp directory");
+            std::fs::create_dir(temp_dir.path().join(".git")).expect("should create .git");
+            let crate_dir = temp_dir.path().join("tools").join("cc-check");
+            let src_dir = crate_dir.join("src");
+            std::fs::create_dir_all(&src_dir).expect("should create crate directory");
+            std::fs::write(crate_dir.join("Cargo.toml"), "[package]\nname = \"test\"")
+                .expect("should write Cargo.toml");
+
+            std::env::set_current_dir(&src_dir).unwrap();
+            let crate_root = find_crate_root().expect("should find crate root");
+            let repo_root = find_repo_root().expect("should find repo root");
+            // Use canonicalize to handle symlink differences (e.g., /var vs /private/var on macOS)
+            assert_eq!(
+                std::fs::canonicalize(&crate_root).unwrap(),
+                std::fs::canonicalize(&crate_dir).unwrap()
+            );
+            assert_eq!(
+                std::fs::canonicalize(&repo_root).unwrap(),
+                std::fs::canonicalize(temp_dir.path()).unwrap()
+            );
+        }
+
         #[test]
         fn find_repo_root_fails_when_no_repo_found() {
             use tempfile::TempDir;
@@ -1,14 +1,28 @@
 use anyhow::{bail, Context, Result};
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 use serde::Serialize;
+use std::borrow::Cow;
 use std::fs;
 use std::io::{self, IsTerminal, Read};
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+use cc_check::branch::{self, BranchMode, BranchRules};
+use cc_check::cleanup::{self, CleanupMode};
+use cc_check::config::{Config, ConfigEntry, Origin, ResolvedConfig};
+use cc_check::content::{ContentMatch, ContentRules, Severity};
+use cc_check::custom_rules::{self, CustomRule, Violation};
+use cc_check::git_message::{self, GitMessageKind};
+use cc_check::plugin::{self, Plugin};
+use cc_check::references::{Reference, ReferenceRules};
+use cc_check::script::ScriptRules;
+use cc_check::signoff::{self, Identity};
+use cc_check::trailers::{Mailmap, TrailerRules};
 use cc_check::{
-    find_repo_root, first_meaningful_line, is_merge_like_header, validate_header, ValidationError,
+    find_crate_root, first_meaningful_line, is_commit_hash, strip_autosquash_prefix,
+    validate_header_with_rules, Autosquash, HeaderRules, LengthUnit, ValidationError,
 };
+use cc_check::{revert, skip};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum OutputFormat {
@@ -32,15 +46,16 @@ impl std::str::FromStr for OutputFormat {
     name = "cc-check",
     about = "Validate commit messages against Conventional Commits",
     version,
-    arg_required_else_help = false
+    arg_required_else_help = false,
+    args_conflicts_with_subcommands = true
 )]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Path to the commit message file (for backward compatibility)
-    #[arg(value_name = "COMMIT_MSG_FILE", hide = true)]
-    commit_msg_file: Option<PathBuf>,
+    /// Legacy `cc-check <COMMIT_MSG_FILE> [flags]` form, equivalent to `cc-check check`
+    #[command(flatten)]
+    legacy: CheckArgs,
 }
 
 #[derive(Debug, Subcommand)]
@@ -52,30 +67,96 @@ enum Commands {
         no_build: bool,
     },
     /// Validate a commit message
-    Check {
-        /// Path to the commit message file (as provided to commit-msg hook)
-        #[arg(value_name = "COMMIT_MSG_FILE", required = false)]
-        commit_msg_file: Option<PathBuf>,
+    Check(CheckArgs),
+    /// Inspect the cc-check configuration
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+    /// Convert another tool's commit lint config into a .cc-check.toml
+    Migrate {
+        /// Tool to migrate from: commitlint
+        #[arg(long, value_name = "TOOL")]
+        from: MigrateSource,
 
-        /// Allow types in addition to the default list (comma-separated)
-        #[arg(long, value_name = "TYPES")]
-        extra_types: Option<String>,
+        /// Config file to read (defaults to the one in the current directory)
+        #[arg(value_name = "CONFIG_FILE")]
+        input: Option<PathBuf>,
 
-        /// Enforce max subject length (0 to disable)
-        #[arg(long)]
-        max_subject: Option<usize>,
+        /// Where to write the converted config
+        #[arg(long, short, value_name = "FILE", default_value = ".cc-check.toml")]
+        output: PathBuf,
 
-        /// Disallow trailing period in subject
+        /// Overwrite the output file if it exists
         #[arg(long)]
-        no_trailing_period: Option<bool>,
+        force: bool,
+    },
+}
 
-        /// Ignore comment lines (starting with '#') in commit message
-        #[arg(long)]
-        ignore_comments: Option<bool>,
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MigrateSource {
+    Commitlint,
+}
 
-        /// Allow merge-like messages (e.g., 'Merge ...' or 'Revert ...') to pass
-        #[arg(long)]
-        allow_merge_commits: Option<bool>,
+impl std::str::FromStr for MigrateSource {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "commitlint" => Ok(MigrateSource::Commitlint),
+            _ => Err("supported tools: commitlint".to_string()),
+        }
+    }
+}
+
+/// Arguments of `cc-check check`, also accepted by the legacy bare invocation
+#[derive(Debug, Args)]
+struct CheckArgs {
+    /// Path to the commit message file (as provided to commit-msg hook)
+    #[arg(value_name = "COMMIT_MSG_FILE", required = false)]
+    commit_msg_file: Option<PathBuf>,
+
+    /// Check every commit in a revision range (e.g. origin/main..HEAD) instead
+    #[arg(long, value_name = "REV_RANGE", conflicts_with = "commit_msg_file")]
+    range: Option<String>,
+
+    /// Fix what can be fixed in the message file, e.g. add a missing sign-off
+    #[arg(long, conflicts_with = "range")]
+    fix: bool,
+
+    #[command(flatten)]
+    flags: ConfigFlags,
+
+    /// Output format: text or json
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        env = "CC_CHECK_FORMAT",
+        default_value = "text"
+    )]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommands {
+    /// Validate the configuration file and exit non-zero on errors
+    Validate {
+        /// Path to the config file (defaults to CC_CHECK_CONFIG or the discovered config)
+        #[arg(value_name = "CONFIG_FILE")]
+        path: Option<PathBuf>,
+    },
+    /// Print the effective configuration and where each value comes from
+    Show {
+        #[command(flatten)]
+        flags: ConfigFlags,
+
+        /// Output format: toml or json
+        #[arg(long, value_name = "FORMAT", default_value = "toml")]
+        format: ShowFormat,
+    },
+    /// List the allowed commit types with their metadata
+    Types {
+        #[command(flatten)]
+        flags: ConfigFlags,
 
         /// Output format: text or json
         #[arg(long, value_name = "FORMAT", default_value = "text")]
@@ -83,95 +164,359 @@ enum Commands {
     },
 }
 
+/// Flags that override values from the config file
+#[derive(Debug, Args)]
+struct ConfigFlags {
+    /// Path to the config file (overrides CC_CHECK_CONFIG and config discovery)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Use a built-in convention preset (angular, conventional, atom, ember, eslint, jquery)
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
+
+    /// Allow types in addition to the default list (comma-separated)
+    #[arg(long, value_name = "TYPES")]
+    extra_types: Option<String>,
+
+    /// Enforce max subject length (0 to disable)
+    #[arg(long)]
+    max_subject: Option<usize>,
+
+    /// Enforce min subject length (0 to disable)
+    #[arg(long)]
+    min_subject: Option<usize>,
+
+    /// Enforce max length of the whole header line (0 to disable)
+    #[arg(long)]
+    max_header: Option<usize>,
+
+    /// Measure lengths in chars or display columns (wide characters count as two)
+    #[arg(long, value_name = "UNIT")]
+    length_unit: Option<LengthUnit>,
+
+    /// Disallow trailing period in subject
+    #[arg(long)]
+    no_trailing_period: Option<bool>,
+
+    /// Ignore comment lines (starting with the comment char, '#' by default) in commit message
+    #[arg(long)]
+    ignore_comments: Option<bool>,
+
+    /// Message cleanup mode, as git's commit.cleanup (default, strip, whitespace, verbatim, scissors)
+    #[arg(long, value_name = "MODE")]
+    cleanup: Option<CleanupMode>,
+
+    /// Allow git-generated merge and revert messages (see `git_messages`) to pass
+    #[arg(long)]
+    allow_merge_commits: Option<bool>,
+
+    /// Reject unsquashed fixup!/squash!/amend! commits in --range mode
+    #[arg(long)]
+    forbid_unsquashed: Option<bool>,
+}
+
+impl ConfigFlags {
+    /// The flags as a config layer; unset flags leave lower layers untouched
+    fn to_config(&self) -> Config {
+        Config {
+            preset: self.preset.clone(),
+            extra_types: self
+                .extra_types
+                .as_deref()
+                .map(split_list)
+                .unwrap_or_default(),
+            max_subject: self.max_subject,
+            min_subject: self.min_subject,
+            max_header: self.max_header,
+            length_unit: self.length_unit,
+            no_trailing_period: self.no_trailing_period,
+            ignore_comments: self.ignore_comments,
+            cleanup: self.cleanup,
+            allow_merge_commits: self.allow_merge_commits,
+            forbid_unsquashed: self.forbid_unsquashed,
+            ..Config::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShowFormat {
+    Toml,
+    Json,
+}
+
+impl std::str::FromStr for ShowFormat {
+    type Err = String;
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "toml" => Ok(ShowFormat::Toml),
+            "json" => Ok(ShowFormat::Json),
+            _ => Err("supported formats: toml, json".to_string()),
+        }
+    }
+}
+
 #[derive(Serialize)]
 struct JsonResult<'a> {
     ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     error: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[Reference]>::is_empty")]
+    references: &'a [Reference],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    hints: &'a [String],
+    #[serde(skip_serializing_if = "<[ContentMatch]>::is_empty")]
+    findings: &'a [ContentMatch],
+    #[serde(skip_serializing_if = "<[Violation]>::is_empty")]
+    violations: &'a [Violation],
+}
+
+/// What a successful validation found out about the message
+#[derive(Default)]
+struct Checked {
+    /// Issue references, in message order
+    references: Vec<Reference>,
+    /// Suggestions that don't fail the check
+    hints: Vec<String>,
+    /// Forbidden content reported as a warning
+    findings: Vec<ContentMatch>,
+    /// Custom rules broken at warning severity
+    violations: Vec<Violation>,
+}
+
+/// Result for one commit in `check --range`
+#[derive(Serialize)]
+struct RangeCommit {
+    sha: String,
+    header: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    references: Vec<Reference>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    findings: Vec<ContentMatch>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    violations: Vec<Violation>,
+}
+
+/// Report for `check --range`
+#[derive(Serialize)]
+struct RangeReport {
+    ok: bool,
+    commits: Vec<RangeCommit>,
 }
 
 /// Options for checking commit messages
 struct CheckOptions {
     /// Path to the commit message file
     commit_msg_file: Option<PathBuf>,
-    /// Additional commit types to allow (comma-separated)
-    extra_types: Option<String>,
-    /// Maximum subject length (0 to disable)
-    max_subject: usize,
-    /// Disallow trailing period in subject
-    no_trailing_period: bool,
-    /// Ignore comment lines (starting with '#') in commit message
-    ignore_comments: bool,
-    /// Allow merge-like messages (e.g., 'Merge ...' or 'Revert ...') to pass
+    /// Revision range to check instead of a single message
+    range: Option<String>,
+    /// Header pattern, allowed types and subject rules
+    rules: HeaderRules,
+    /// Cleanup applied to the message before validation (never `Default`)
+    cleanup: CleanupMode,
+    /// Comment prefix, or "auto"
+    comment: String,
+    /// Allow git-generated merge and revert messages (see `git_messages`) to pass
     allow_merge_commits: bool,
+    /// Git-generated message kinds let through when `allow_merge_commits` is on
+    git_messages: Vec<GitMessageKind>,
+    /// Issue-reference patterns and requirements
+    references: ReferenceRules,
+    /// Branch-name rule
+    branch: BranchRules,
+    /// Require a `Signed-off-by` trailer
+    signoff_required: bool,
+    /// Require a sign-off by the commit author
+    signoff_match_author: bool,
+    /// Append a missing sign-off to the message file
+    fix: bool,
+    /// Forbidden-content rules
+    content: ContentRules,
+    /// `[[custom_rules]]` from the config
+    custom_rules: Vec<CustomRule>,
+    /// `[[plugins]]` from the config
+    plugins: Vec<Plugin>,
+    /// Rhai rule scripts
+    scripts: ScriptRules,
+    /// Allowed and required trailers and their formats
+    trailers: TrailerRules,
+    /// Mailmap co-authors must be listed in, if `trailers.mailmap` is on
+    mailmap: Option<Mailmap>,
+    /// Require `revert` commits to reference the reverted commit
+    require_revert_refs: bool,
+    /// Accept autosquash commits whose target header is valid
+    allow_autosquash: bool,
+    /// Reject autosquash commits in range mode
+    forbid_unsquashed: bool,
+    /// Let commits bypass validation with `CC_CHECK_SKIP` or the skip marker
+    allow_skip: bool,
+    /// Marker that bypasses validation ("" if disabled)
+    skip_marker: String,
     /// Output format: text or json
     format: OutputFormat,
 }
 
 impl CheckOptions {
-    /// Create default options for backward compatibility
-    fn default_with_file(commit_msg_file: Option<PathBuf>) -> Self {
-        Self {
-            commit_msg_file,
-            extra_types: None,
-            max_subject: 72,
-            no_trailing_period: true,
-            ignore_comments: true,
-            allow_merge_commits: true,
-            format: OutputFormat::Text,
-        }
-    }
-
     /// Create options from CLI args and config file
     /// CLI args take precedence over config file settings
-    fn from_cli_and_config(
-        commit_msg_file: Option<PathBuf>,
-        cli_extra_types: Option<String>,
-        cli_max_subject: Option<usize>,
-        cli_no_trailing_period: Option<bool>,
-        cli_ignore_comments: Option<bool>,
-        cli_allow_merge_commits: Option<bool>,
-        format: OutputFormat,
-    ) -> Self {
-        // Try to load config file
-        let config = cc_check::config::Config::load_from_repo()
-            .ok()
-            .flatten()
-            .unwrap_or_default();
-
-        // Merge CLI args with config file (CLI takes precedence)
-        let extra_types = cli_extra_types.or_else(|| {
-            if config.extra_types.is_empty() {
-                None
+    /// An invalid config file is an error rather than silently falling back to defaults
+    fn from_args(args: CheckArgs) -> Result<Self> {
+        let config = resolve_config(&args.flags, args.range.is_none())?.config()?;
+        let root = repo_root()?;
+
+        Ok(Self {
+            commit_msg_file: args.commit_msg_file,
+            range: args.range,
+            rules: config.header_rules()?,
+            cleanup: resolve_cleanup(&config),
+            comment: config
+                .comment_char
+                .clone()
+                .or_else(|| git_config("core.commentString"))
+                .or_else(|| git_config("core.commentChar"))
+                .unwrap_or_else(|| cleanup::DEFAULT_COMMENT.to_string()),
+            allow_merge_commits: config.allow_merge_commits.unwrap_or(true),
+            git_messages: config
+                .git_messages
+                .clone()
+                .unwrap_or_else(|| GitMessageKind::ALL.to_vec()),
+            references: config.references.rules()?,
+            branch: config.branch.rules()?,
+            signoff_required: config.signoff.required.unwrap_or(false),
+            signoff_match_author: config.signoff.match_author.unwrap_or(false),
+            fix: args.fix,
+            content: ContentRules::new(&config.content)?,
+            custom_rules: custom_rules::compile(&config.custom_rules)?,
+            plugins: plugin::load(&config.plugins, &root)?,
+            scripts: ScriptRules::load(&config.scripts, &root, || branch::current_branch(&root))?,
+            trailers: config.trailers.rules()?,
+            mailmap: if config.trailers.mailmap.unwrap_or(false) {
+                Some(load_mailmap()?)
             } else {
-                Some(config.extra_types.join(","))
-            }
-        });
+                None
+            },
+            require_revert_refs: config.require_revert_refs.unwrap_or(false),
+            allow_autosquash: config.allow_autosquash.unwrap_or(true),
+            forbid_unsquashed: config.forbid_unsquashed.unwrap_or(false),
+            allow_skip: config.allow_skip.unwrap_or(true),
+            skip_marker: config.skip_marker.unwrap_or_default(),
+            format: args.format,
+        })
+    }
+}
+
+/// The cleanup mode to apply: the configured one, with "default" following the
+/// repository's `commit.cleanup`; `ignore_comments = false` keeps comment lines
+fn resolve_cleanup(config: &Config) -> CleanupMode {
+    let mode = match config.cleanup.unwrap_or_default() {
+        CleanupMode::Default => git_config("commit.cleanup")
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(CleanupMode::Strip),
+        mode => mode,
+    };
+    match mode {
+        CleanupMode::Default | CleanupMode::Strip if !config.ignore_comments.unwrap_or(true) => {
+            CleanupMode::Whitespace
+        }
+        CleanupMode::Default => CleanupMode::Strip,
+        mode => mode,
+    }
+}
 
-        let max_subject = cli_max_subject.or(config.max_subject).unwrap_or(72);
+/// Top level of the git working tree, or the current directory outside one
+fn repo_root() -> Result<PathBuf> {
+    let cwd = std::env::current_dir()?;
+    Ok(cc_check::repo::RepoPaths::discover(&cwd)
+        .git_root
+        .unwrap_or(cwd))
+}
 
-        let no_trailing_period = cli_no_trailing_period
-            .or(config.no_trailing_period)
-            .unwrap_or(true);
+/// The mailmap git would use: `mailmap.file`, else `.mailmap` at the repository root
+fn load_mailmap() -> Result<Mailmap> {
+    let path = match git_config("mailmap.file") {
+        Some(file) => PathBuf::from(file),
+        None => {
+            let repo = cc_check::repo::RepoPaths::discover(&std::env::current_dir()?);
+            repo.git_root
+                .context("trailers.mailmap is on but this is not a git repository")?
+                .join(".mailmap")
+        }
+    };
+    Mailmap::load(&path)
+}
 
-        let ignore_comments = cli_ignore_comments
-            .or(config.ignore_comments)
-            .unwrap_or(true);
+fn git_config(key: &str) -> Option<String> {
+    cc_check::repo::git_config(Path::new("."), key)
+}
 
-        let allow_merge_commits = cli_allow_merge_commits
-            .or(config.allow_merge_commits)
-            .unwrap_or(true);
+/// Layer the repository config file(s) and CLI flags over the defaults; `staged` picks
+/// the monorepo configs by the staged paths (see [`config_files`])
+fn resolve_config(flags: &ConfigFlags, staged: bool) -> Result<ResolvedConfig> {
+    let mut resolved = ResolvedConfig::new();
+    for path in config_files(flags, staged)? {
+        resolved.push_file(&path)?;
+    }
+    print_notes(&resolved);
+    resolved.push(Origin::Env, &Config::from_env()?)?;
+    resolved.push(Origin::Cli, &flags.to_config())?;
+    Ok(resolved)
+}
 
-        Self {
-            commit_msg_file,
-            extra_types,
-            max_subject,
-            no_trailing_period,
-            ignore_comments,
-            allow_merge_commits,
-            format,
+/// Config files to apply, lowest precedence first
+///
+/// An explicit `--config` or `CC_CHECK_CONFIG` is used on its own. Otherwise, in a
+/// monorepo, the configs of every directory from the git root down to the nearest
+/// common ancestor of the staged paths apply, deeper ones overriding shallower ones.
+/// A commit spanning several packages therefore only gets the configs they share.
+/// Without `staged` (in `check --range`, where the index has nothing to do with the
+/// commits checked), only the config at the git root applies.
+fn config_files(flags: &ConfigFlags, staged: bool) -> Result<Vec<PathBuf>> {
+    if let Some(path) = &flags.config {
+        return Ok(vec![path.clone()]);
+    }
+    if std::env::var_os(cc_check::config::CONFIG_ENV_VAR).is_some_and(|p| !p.is_empty()) {
+        return Ok(Config::find_config_file().into_iter().collect());
+    }
+
+    let repo = cc_check::repo::RepoPaths::discover(&std::env::current_dir()?);
+    if let Some(git_root) = &repo.git_root {
+        let dir = if staged {
+            cc_check::repo::common_directory(&cc_check::repo::staged_paths(git_root))
+        } else {
+            Some(PathBuf::new())
+        };
+        if let Some(dir) = dir {
+            let chain = Config::find_config_chain(git_root, &dir);
+            if !chain.is_empty() {
+                return Ok(chain);
+            }
         }
     }
+
+    Ok(Config::find_config_file().into_iter().collect())
+}
+
+/// Report what a commitlint config used as cc-check config could not carry over
+fn print_notes(resolved: &ResolvedConfig) {
+    for note in resolved.notes() {
+        eprintln!("note: {note}");
+    }
+}
+
+fn split_list(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
 }
 
 fn main() -> Result<()> {
@@ -179,30 +524,36 @@ fn main() -> Result<()> {
 
     match cli.command {
         Some(Commands::Install { no_build }) => install_hook(no_build),
-        Some(Commands::Check {
-            commit_msg_file,
-            extra_types,
-            max_subject,
-            no_trailing_period,
-            ignore_comments,
-            allow_merge_commits,
-            format,
-        }) => check_commit(CheckOptions::from_cli_and_config(
-            commit_msg_file,
-            extra_types,
-            max_subject,
-            no_trailing_period,
-            ignore_comments,
-            allow_merge_commits,
-            format,
-        )),
+        Some(Commands::Check(args)) => check_commit(CheckOptions::from_args(args)?),
+        Some(Commands::Config { command }) => match command {
+            ConfigCommands::Validate { path } => validate_config(path),
+            ConfigCommands::Show { flags, format } => show_config(&flags, format),
+            ConfigCommands::Types { flags, format } => list_types(&flags, format),
+        },
+        Some(Commands::Migrate {
+            from,
+            input,
+            output,
+            force,
+        }) => migrate(from, input, &output, force),
         None => {
             // Default behavior: check commit message (backward compatibility)
-            check_commit(CheckOptions::default_with_file(cli.commit_msg_file))
+            if cli.legacy.commit_msg_file.is_some() {
+                deprecation_notice("`cc-check <COMMIT_MSG_FILE>` is deprecated; use `cc-check check <COMMIT_MSG_FILE>`");
+            }
+            check_commit(CheckOptions::from_args(cli.legacy)?)
         }
     }
 }
 
+/// Print a deprecation warning to stderr when `CC_CHECK_DEPRECATIONS` is set, so
+/// hook scripts relying on a form that is going away can be found before it is removed
+fn deprecation_notice(message: &str) {
+    if std::env::var_os(cc_check::config::DEPRECATIONS_ENV_VAR).is_some_and(|v| !v.is_empty()) {
+        eprintln!("warning: {message}");
+    }
+}
+
 fn install_hook(no_build: bool) -> Result<()> {
     // Find git directory
     let git_dir_output = Command::new("git")
@@ -259,8 +610,8 @@ fn install_hook(no_build: bool) -> Result<()> {
 
     // If we're running from cargo run, try to find the built binary
     let binary_path = if !binary_path.exists() {
-        let repo_root = find_repo_root()?;
-        let release_bin = repo_root.join("target/release").join(exe_name);
+        let crate_root = find_crate_root()?;
+        let release_bin = crate_root.join("target/release").join(exe_name);
         if release_bin.exists() {
             release_bin
         } else {
@@ -361,25 +712,167 @@ exec {} check "$1"
     ))
 }
 
-fn check_commit(options: CheckOptions) -> Result<()> {
-    // Default allowed types follow the Angular convention (widely adopted with Conventional Commits).
-    // This includes all 11 standard types: feat, fix, docs, style, refactor, perf, test, build, ci, chore, revert.
-    // Reference: https://www.conventionalcommits.org/
-    // Reference: https://github.com/angular/angular/blob/main/CONTRIBUTING.md#type
-    let allowed_types_default = [
-        "feat", "fix", "chore", "docs", "style", "refactor", "perf", "test", "build", "ci",
-        "revert",
-    ];
-
-    let mut allowed_types = allowed_types_default.map(String::from).to_vec();
-    if let Some(extra) = &options.extra_types {
-        for t in extra.split(',') {
-            let t = t.trim();
-            if !t.is_empty() && !allowed_types.iter().any(|x| x == t) {
-                allowed_types.push(t.to_string());
+fn validate_config(path: Option<PathBuf>) -> Result<()> {
+    let path = match path.or_else(cc_check::config::Config::find_config_file) {
+        Some(path) => path,
+        None => {
+            println!("No cc-check configuration found; using default configuration");
+            return Ok(());
+        }
+    };
+
+    let mut resolved = ResolvedConfig::new();
+    match resolved.push_file(&path).and_then(|()| resolved.config()) {
+        Ok(_) => {
+            print_notes(&resolved);
+            println!("✓ {} is valid", path.display());
+            Ok(())
+        }
+        Err(err) => {
+            eprintln!("Invalid configuration: {err:#}");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn migrate(from: MigrateSource, input: Option<PathBuf>, output: &Path, force: bool) -> Result<()> {
+    let MigrateSource::Commitlint = from;
+    let input = match input {
+        Some(path) => path,
+        None => cc_check::commitlint::find_in_dir(&std::env::current_dir()?)?
+            .context("no commitlint config found in the current directory")?,
+    };
+    if output.exists() && !force {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            output.display()
+        );
+    }
+
+    let conversion = cc_check::commitlint::load(&input)?;
+    for note in &conversion.notes {
+        eprintln!("note: {note}");
+    }
+    let contents = format!(
+        "# Migrated from {} by `cc-check migrate`\n\n{}",
+        input
+            .file_name()
+            .unwrap_or(input.as_os_str())
+            .to_string_lossy(),
+        conversion.config.to_toml()?
+    );
+    fs::write(output, contents).with_context(|| format!("failed to write {}", output.display()))?;
+    println!("✓ wrote {}", output.display());
+    Ok(())
+}
+
+fn show_config(flags: &ConfigFlags, format: ShowFormat) -> Result<()> {
+    let entries = resolve_config(flags, true)?.entries()?;
+
+    match format {
+        ShowFormat::Toml => {
+            let repo = cc_check::repo::RepoPaths::discover(&std::env::current_dir()?);
+            for (label, path) in [
+                ("git root", &repo.git_root),
+                ("package root", &repo.package_root),
+            ] {
+                if let Some(path) = path {
+                    println!("# {label}: {}", path.display());
+                }
+            }
+            print!("{}", render_config_toml(&entries)?)
+        }
+        ShowFormat::Json => println!("{}", render_config_json(&entries)?),
+    }
+    Ok(())
+}
+
+fn list_types(flags: &ConfigFlags, format: OutputFormat) -> Result<()> {
+    let definitions = resolve_config(flags, true)?.config()?.type_definitions()?;
+
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&definitions)?),
+        OutputFormat::Text if definitions.is_empty() => println!("Any type is allowed"),
+        OutputFormat::Text => {
+            let width = definitions.iter().map(|d| d.name.len()).max().unwrap_or(0);
+            for definition in &definitions {
+                let info = &definition.info;
+                let mut details = Vec::new();
+                if let Some(semver) = info.semver {
+                    details.push(format!("semver: {}", semver.as_str()));
+                }
+                if let Some(section) = &info.changelog_section {
+                    details.push(format!("changelog: {section}"));
+                }
+                let details = if details.is_empty() {
+                    String::new()
+                } else {
+                    format!(" [{}]", details.join(", "))
+                };
+                println!(
+                    "{:width$}  {}{}",
+                    definition.name,
+                    info.description.as_deref().unwrap_or(""),
+                    details
+                );
             }
         }
     }
+    Ok(())
+}
+
+/// Render entries as TOML with the origin of each value as a trailing comment
+fn render_config_toml(entries: &[ConfigEntry]) -> Result<String> {
+    let mut out = String::from("# Effective cc-check configuration\n");
+    for entry in entries {
+        let mut table = toml::Table::new();
+        table.insert("v".to_string(), entry.value.clone());
+        let rendered = match &entry.value {
+            // Arrays of tables would be rendered as `[[v]]` sections; keep them inline
+            toml::Value::Array(items) if items.iter().any(toml::Value::is_table) => {
+                format!("v = {}", entry.value)
+            }
+            _ => toml::to_string(&table)?,
+        };
+        let value = rendered.trim_end().trim_start_matches("v = ");
+        out.push_str(&format!(
+            "{} = {}  # {}\n",
+            entry.key,
+            value,
+            entry.origin.describe(&entry.key)
+        ));
+    }
+    Ok(out)
+}
+
+fn render_config_json(entries: &[ConfigEntry]) -> Result<String> {
+    let mut object = serde_json::Map::new();
+    for entry in entries {
+        let mut value = serde_json::Map::new();
+        value.insert("value".to_string(), serde_json::to_value(&entry.value)?);
+        let origin = match &entry.origin {
+            Origin::Default => serde_json::json!({ "kind": "default" }),
+            Origin::Preset(name) => serde_json::json!({ "kind": "preset", "name": name }),
+            Origin::File(path) => serde_json::json!({ "kind": "file", "path": path }),
+            Origin::Env => serde_json::json!({
+                "kind": "env",
+                "var": cc_check::config::env_var(&entry.key),
+            }),
+            Origin::Cli => serde_json::json!({
+                "kind": "cli",
+                "flag": cc_check::config::cli_flag(&entry.key),
+            }),
+        };
+        value.insert("origin".to_string(), origin);
+        object.insert(entry.key.clone(), serde_json::Value::Object(value));
+    }
+    Ok(serde_json::to_string_pretty(&object)?)
+}
+
+fn check_commit(options: CheckOptions) -> Result<()> {
+    if let Some(range) = &options.range {
+        return check_range(range, &options);
+    }
 
     let message = if let Some(path) = &options.commit_msg_file {
         fs::read_to_string(path)
@@ -397,48 +890,491 @@ fn check_commit(options: CheckOptions) -> Result<()> {
         bail!("no commit message file provided");
     };
 
-    let header_opt = first_meaningful_line(&message, options.ignore_comments);
-    let header = match header_opt {
-        Some(h) => h,
-        None => return exit_with(options.format, Err(ValidationError::Empty)),
+    if options.allow_skip {
+        let env_value = std::env::var(skip::SKIP_ENV_VAR).ok();
+        if let Some(reason) =
+            skip::skip_reason(&message, &options.skip_marker, env_value.as_deref())
+        {
+            // Skipping waives the format rules, not the forbidden-content scan
+            if let Err(err) = scan_content(&cleaned_message(&message, &options), &options) {
+                return exit_with(options.format, Err(err));
+            }
+            return skip_commit(&message, &reason, &options);
+        }
+    }
+
+    let author = if options.signoff_match_author {
+        cc_check::repo::git_ident(Path::new("."), "GIT_AUTHOR_IDENT")
+            .and_then(|ident| Identity::parse(&ident))
+    } else {
+        None
     };
+    let mut result = validate_message(&message, author.as_ref(), &options);
 
-    if options.allow_merge_commits && is_merge_like_header(&header) {
-        return exit_with(options.format, Ok(()));
+    if let (
+        true,
+        Some(path),
+        Err(ValidationError::MissingSignoff | ValidationError::SignoffMismatch(_)),
+    ) = (options.fix, &options.commit_msg_file, &result)
+    {
+        match cc_check::repo::git_ident(Path::new("."), "GIT_COMMITTER_IDENT")
+            .and_then(|ident| Identity::parse(&ident))
+        {
+            Some(identity) => {
+                let trailer = signoff::trailer(&identity);
+                let fixed = cc_check::message::append_trailer(&message, &trailer, &options.comment);
+                fs::write(path, &fixed).with_context(|| {
+                    format!("failed to write commit message file: {}", path.display())
+                })?;
+                eprintln!("cc-check: added `{trailer}`");
+                result = validate_message(&fixed, author.as_ref(), &options);
+            }
+            None => eprintln!(
+                "cc-check: cannot add a sign-off without a git identity (set user.name and user.email)"
+            ),
+        }
     }
 
-    let res = validate_header(
-        &header,
-        &allowed_types,
-        options.max_subject,
-        options.no_trailing_period,
+    exit_with(options.format, result)
+}
+
+/// Validate a complete commit message against the configured rules
+///
+/// `author` is the commit author, checked against the sign-offs when `match_author` is on
+fn validate_message(
+    message: &str,
+    author: Option<&Identity>,
+    options: &CheckOptions,
+) -> std::result::Result<Checked, ValidationError> {
+    let message = cleaned_message(message, options);
+
+    // Checked before the format rules, so secrets are caught in merge and fixup commits
+    // too (and in skipped commits, which are scanned on their own)
+    let warnings = scan_content(&message, options)?;
+
+    let mut checked = validate_cleaned(&message, author, options)?;
+    checked.findings = warnings;
+    Ok(checked)
+}
+
+/// `message` cleaned up as git will store it; commits already in history (range mode)
+/// were cleaned up by git when they were made
+fn cleaned_message<'a>(message: &'a str, options: &CheckOptions) -> Cow<'a, str> {
+    if options.range.is_some() {
+        Cow::Borrowed(message)
+    } else {
+        Cow::Owned(cleanup::cleanup(message, options.cleanup, &options.comment))
+    }
+}
+
+/// Scan a cleaned-up message for forbidden content: error-level matches fail, the
+/// warnings are returned
+fn scan_content(
+    message: &str,
+    options: &CheckOptions,
+) -> std::result::Result<Vec<ContentMatch>, ValidationError> {
+    let (errors, warnings): (Vec<_>, Vec<_>) = options
+        .content
+        .scan(message)
+        .into_iter()
+        .partition(|found| found.severity == Severity::Error);
+    if errors.is_empty() {
+        Ok(warnings)
+    } else {
+        Err(ValidationError::ForbiddenContent(errors))
+    }
+}
+
+/// Validate a cleaned-up message against the commit-format rules
+fn validate_cleaned(
+    message: &str,
+    author: Option<&Identity>,
+    options: &CheckOptions,
+) -> std::result::Result<Checked, ValidationError> {
+    let header = first_meaningful_line(message, false).ok_or(ValidationError::Empty)?;
+
+    if let Some(kind) = git_message::classify(&header, message) {
+        if options.allow_merge_commits && options.git_messages.contains(&kind) {
+            return Ok(Checked::default());
+        }
+        if kind == GitMessageKind::Revert {
+            return Err(ValidationError::GitRevert);
+        }
+    }
+
+    if let Some((kind, target)) = strip_autosquash_prefix(&header) {
+        let in_range = options.range.is_some();
+        if !options.allow_autosquash || (in_range && options.forbid_unsquashed) {
+            return Err(ValidationError::UnsquashedCommit(kind));
+        }
+        if is_commit_hash(target) {
+            return Ok(Checked::default());
+        }
+        validate_header_with_rules(target, &options.rules)?;
+        // After autosquash, an `amend!` commit's first body line becomes the header
+        if kind == Autosquash::Amend {
+            let body = message.trim_start().split_once('\n').map(|(_, body)| body);
+            if let Some(replacement) = body.and_then(|body| first_meaningful_line(body, false)) {
+                validate_header_with_rules(&replacement, &options.rules)?;
+            }
+        }
+        return Ok(Checked::default());
+    }
+
+    validate_header_with_rules(&header, &options.rules)?;
+    let parsed = options
+        .rules
+        .parse(&header)
+        .ok_or(ValidationError::BadHeader)?;
+    let parsed_message = cc_check::message::parse(message);
+
+    let references = options.references.extract(&parsed_message, parsed.subject);
+    if references.is_empty() && options.references.required(parsed.commit_type) {
+        return Err(ValidationError::MissingReference(
+            parsed.commit_type.to_string(),
+        ));
+    }
+
+    if options.signoff_required {
+        signoff::check(&parsed_message, author)?;
+    }
+    options
+        .trailers
+        .check(&parsed_message, options.mailmap.as_ref())?;
+
+    let mut hints = Vec::new();
+    if options.range.is_none() && options.branch.mode != BranchMode::Off {
+        check_branch(parsed.commit_type, message, options, &mut hints)?;
+    }
+
+    if options.require_revert_refs {
+        check_revert_refs(&header, message, options)?;
+    }
+
+    let mut violations: Vec<Violation> = options
+        .custom_rules
+        .iter()
+        .filter_map(|rule| rule.check(&parsed, &parsed_message, message))
+        .collect();
+    if !options.plugins.is_empty() || !options.scripts.is_empty() {
+        let request = plugin::Request::new(message, &parsed, &parsed_message);
+        let found = options
+            .scripts
+            .run(&request.commit)
+            .map_err(|(script, reason)| ValidationError::ScriptFailed(script, reason))?;
+        violations.extend(found);
+        for plugin in &options.plugins {
+            let found = plugin
+                .run(&request)
+                .map_err(|reason| ValidationError::PluginFailed(plugin.id.clone(), reason))?;
+            violations.extend(found);
+        }
+    }
+    let (errors, violations): (Vec<_>, Vec<_>) = violations
+        .into_iter()
+        .partition(|violation| violation.severity == Severity::Error);
+    if !errors.is_empty() {
+        return Err(ValidationError::CustomRule(errors));
+    }
+
+    Ok(Checked {
+        references,
+        hints,
+        violations,
+        ..Checked::default()
+    })
+}
+
+/// Compare the commit with what the current branch name implies; disagreements are
+/// errors in enforce mode and hints in suggest mode
+fn check_branch(
+    commit_type: &str,
+    message: &str,
+    options: &CheckOptions,
+    hints: &mut Vec<String>,
+) -> std::result::Result<(), ValidationError> {
+    let Some(branch) = branch::current_branch(Path::new(".")) else {
+        return Ok(());
+    };
+    let Some(expected) = options.branch.expectation(&branch) else {
+        return Ok(());
+    };
+    let enforce = options.branch.mode == BranchMode::Enforce;
+
+    if let Some(expected_type) = expected.commit_type {
+        if expected_type != commit_type {
+            if enforce {
+                return Err(ValidationError::BranchTypeMismatch {
+                    branch,
+                    expected: expected_type,
+                    found: commit_type.to_string(),
+                });
+            }
+            hints.push(format!(
+                "branch '{branch}' suggests type '{expected_type}' rather than '{commit_type}'"
+            ));
+        }
+    }
+
+    if let Some(ticket) = expected.ticket {
+        if !branch::mentions_ticket(message, &ticket) {
+            if enforce {
+                return Err(ValidationError::MissingBranchTicket { branch, ticket });
+            }
+            hints.push(format!(
+                "add `Refs: {ticket}` to reference the ticket from branch '{branch}'"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// A `revert` commit must name the commit(s) it reverts; in range mode they must exist,
+/// and a single reverted commit's header must match the revert subject
+fn check_revert_refs(
+    header: &str,
+    message: &str,
+    options: &CheckOptions,
+) -> std::result::Result<(), ValidationError> {
+    let Some(parsed) = options.rules.parse(header) else {
+        return Ok(());
+    };
+    if parsed.commit_type != "revert" {
+        return Ok(());
+    }
+
+    let shas = revert::reverted_commits(message);
+    if shas.is_empty() {
+        return Err(ValidationError::MissingRevertRef);
+    }
+    if options.range.is_none() {
+        return Ok(());
+    }
+
+    for sha in &shas {
+        let reverted = cc_check::repo::commit_message(Path::new("."), sha)
+            .ok_or_else(|| ValidationError::UnknownRevertedCommit(sha.clone()))?;
+        let reverted_header = reverted.lines().next().unwrap_or_default().trim();
+        let reverted_subject = options
+            .rules
+            .parse(reverted_header)
+            .map_or(reverted_header, |p| p.subject);
+        if shas.len() == 1
+            && !revert::subject_matches(parsed.subject, reverted_header, reverted_subject)
+        {
+            return Err(ValidationError::RevertSubjectMismatch(
+                sha.clone(),
+                reverted_header.to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Let a commit through without validation, recording the skip as a trailer in the
+/// commit message file so it stays visible in history
+fn skip_commit(message: &str, reason: &str, options: &CheckOptions) -> Result<()> {
+    match &options.commit_msg_file {
+        Some(path) => {
+            fs::write(path, skip::record_skip(message, reason, &options.comment)).with_context(
+                || format!("failed to write commit message file: {}", path.display()),
+            )?;
+            eprintln!(
+                "cc-check: validation skipped ({reason}); recorded as a {} trailer",
+                skip::SKIP_TRAILER
+            );
+        }
+        None => eprintln!("cc-check: validation skipped ({reason})"),
+    }
+
+    if options.format == OutputFormat::Json {
+        println!(
+            "{}",
+            serde_json::to_string(&JsonResult {
+                ok: true,
+                error: None,
+                skipped: Some(reason),
+                references: &[],
+                hints: &[],
+                findings: &[],
+                violations: &[],
+            })?
+        );
+    }
+    Ok(())
+}
+
+/// Validate every commit in `range`, listing the ones that bypassed validation
+fn check_range(range: &str, options: &CheckOptions) -> Result<()> {
+    let commits = cc_check::repo::commits_in_range(&std::env::current_dir()?, range)?;
+
+    let results: Vec<RangeCommit> = commits
+        .iter()
+        .map(|commit| {
+            let header = commit
+                .message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let skipped = if options.allow_skip {
+                skip::recorded_skip(&commit.message, &options.skip_marker)
+                    .or_else(|| skip::skip_reason(&commit.message, &options.skip_marker, None))
+            } else {
+                None
+            };
+            let outcome = match skipped {
+                Some(_) => scan_content(&commit.message, options).map(|findings| Checked {
+                    findings,
+                    ..Checked::default()
+                }),
+                None => {
+                    let author = options
+                        .signoff_match_author
+                        .then(|| Identity::parse(&commit.author))
+                        .flatten();
+                    validate_message(&commit.message, author.as_ref(), options)
+                }
+            };
+            let (error, checked) = match outcome {
+                Ok(checked) => (None, checked),
+                Err(err) => (Some(err.to_string()), Checked::default()),
+            };
+            RangeCommit {
+                sha: commit.sha.clone(),
+                header,
+                ok: error.is_none(),
+                error,
+                skipped,
+                references: checked.references,
+                findings: checked.findings,
+                violations: checked.violations,
+            }
+        })
+        .collect();
+
+    let ok = results.iter().all(|result| result.ok);
+    match options.format {
+        OutputFormat::Json => {
+            let report = RangeReport {
+                ok,
+                commits: results,
+            };
+            println!("{}", serde_json::to_string(&report)?);
+        }
+        OutputFormat::Text => print_range_report(&results),
+    }
+
+    if !ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_range_report(results: &[RangeCommit]) {
+    let short = |sha: &str| sha[..sha.len().min(7)].to_string();
+
+    let failed: Vec<_> = results.iter().filter(|r| !r.ok).collect();
+    for result in &failed {
+        eprintln!(
+            "✗ {} {}: {}",
+            short(&result.sha),
+            result.header,
+            result.error.as_deref().unwrap_or_default()
+        );
+    }
+
+    for result in results {
+        let warnings = result
+            .findings
+            .iter()
+            .map(ToString::to_string)
+            .chain(result.violations.iter().map(ToString::to_string));
+        for warning in warnings {
+            eprintln!(
+                "warning: {} {}: {warning}",
+                short(&result.sha),
+                result.header
+            );
+        }
+    }
+
+    let skipped: Vec<_> = results.iter().filter(|r| r.skipped.is_some()).collect();
+    if !skipped.is_empty() {
+        println!("Skipped commits ({}):", skipped.len());
+        for result in &skipped {
+            println!(
+                "  {} {} ({})",
+                short(&result.sha),
+                result.header,
+                result.skipped.as_deref().unwrap_or_default()
+            );
+        }
+    }
+
+    println!(
+        "Checked {} commits: {} failed, {} skipped",
+        results.len(),
+        failed.len(),
+        skipped.len()
     );
-    exit_with(options.format, res)
 }
 
-fn exit_with(format: OutputFormat, res: std::result::Result<(), ValidationError>) -> Result<()> {
+fn exit_with(
+    format: OutputFormat,
+    res: std::result::Result<Checked, ValidationError>,
+) -> Result<()> {
     match (format, res) {
-        (OutputFormat::Text, Ok(())) => Ok(()),
+        (OutputFormat::Text, Ok(checked)) => {
+            for finding in &checked.findings {
+                eprintln!("warning: {finding}");
+            }
+            for violation in &checked.violations {
+                eprintln!("warning: {violation}");
+            }
+            for hint in &checked.hints {
+                eprintln!("hint: {hint}");
+            }
+            Ok(())
+        }
         (OutputFormat::Text, Err(err)) => {
             eprintln!("Conventional commit check failed: {err}");
             std::process::exit(1);
         }
-        (OutputFormat::Json, Ok(())) => {
+        (OutputFormat::Json, Ok(checked)) => {
             println!(
                 "{}",
                 serde_json::to_string(&JsonResult {
                     ok: true,
-                    error: None
+                    error: None,
+                    skipped: None,
+                    references: &checked.references,
+                    hints: &checked.hints,
+                    findings: &checked.findings,
+                    violations: &checked.violations,
                 })?
             );
             Ok(())
         }
         (OutputFormat::Json, Err(err)) => {
+            let findings = match &err {
+                ValidationError::ForbiddenContent(findings) => findings.as_slice(),
+                _ => &[],
+            };
+            let violations = match &err {
+                ValidationError::CustomRule(violations) => violations.as_slice(),
+                _ => &[],
+            };
             println!(
                 "{}",
                 serde_json::to_string(&JsonResult {
                     ok: false,
-                    error: Some(&err.to_string())
+                    error: Some(&err.to_string()),
+                    skipped: None,
+                    references: &[],
+                    hints: &[],
+                    findings,
+                    violations,
                 })?
             );
             std::process::exit(1);
@@ -0,0 +1,230 @@
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+/// A footer (git trailer) such as `Refs: #12` or `Closes #34`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Footer {
+    /// Footer token, e.g. `Refs` or `BREAKING CHANGE`
+    pub token: String,
+    /// `": "`, or `" #"` for the `Closes #12` form
+    pub separator: String,
+    /// Value, with continuation lines joined by newlines
+    pub value: String,
+    /// 1-based line of the footer in the cleaned-up message
+    pub line: usize,
+}
+
+/// A cleaned-up commit message split into header, body and footers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Message {
+    /// First line
+    pub header: String,
+    /// Paragraphs between the header and the footers
+    pub body: String,
+    /// Footers from the last paragraph, when every line of it is a footer
+    pub footers: Vec<Footer>,
+}
+
+impl Footer {
+    /// The text after the token, keeping the `#` of the `Closes #12` form
+    pub fn text(&self) -> String {
+        match self.separator.as_str() {
+            " #" => format!("#{}", self.value),
+            _ => self.value.clone(),
+        }
+    }
+}
+
+impl Message {
+    /// Footers whose token matches `token` case-insensitively
+    pub fn footers_named<'a>(&'a self, token: &'a str) -> impl Iterator<Item = &'a Footer> {
+        self.footers
+            .iter()
+            .filter(move |footer| footer.token.eq_ignore_ascii_case(token))
+    }
+}
+
+fn footer_line() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"^(?P<token>BREAKING[ -]CHANGE|[A-Za-z0-9][A-Za-z0-9-]*)(?P<separator>: | #)(?P<value>.*)$")
+            .expect("valid regex")
+    })
+}
+
+/// Append a `Token: value` trailer line to a raw commit message
+///
+/// The trailer goes after the last non-comment line (`comment` is the comment prefix,
+/// or "auto"), joining the last paragraph only when [`parse`] reads all of it as
+/// footers and starting a new paragraph otherwise, so git's comment stripping leaves
+/// it in place and it parses as a footer.
+pub fn append_trailer(message: &str, trailer: &str, comment: &str) -> String {
+    let comment = if comment == "auto" {
+        crate::cleanup::detect_comment(message)
+    } else {
+        comment.to_string()
+    };
+    let lines: Vec<&str> = message.lines().collect();
+    let scissors = crate::cleanup::scissors_line(&comment);
+    let end = lines
+        .iter()
+        .position(|line| *line == scissors)
+        .unwrap_or(lines.len());
+    let is_comment = |line: &str| line.trim_start().starts_with(comment.as_str());
+    let last_content = lines[..end]
+        .iter()
+        .rposition(|line| !line.trim().is_empty() && !is_comment(line));
+
+    let mut out: Vec<&str> = Vec::with_capacity(lines.len() + 2);
+    match last_content {
+        Some(index) => {
+            let content: Vec<&str> = lines[..=index]
+                .iter()
+                .copied()
+                .filter(|line| !is_comment(line))
+                .collect();
+            out.extend_from_slice(&lines[..=index]);
+            if parse(&content.join("\n")).footers.is_empty() {
+                out.push("");
+            }
+            out.push(trailer);
+            out.extend_from_slice(&lines[index + 1..]);
+        }
+        None => {
+            out.push(trailer);
+            out.extend_from_slice(&lines);
+        }
+    }
+
+    let mut result = out.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Split a cleaned-up message following the Conventional Commits layout
+pub fn parse(message: &str) -> Message {
+    let lines: Vec<&str> = message.lines().collect();
+    let Some(header_index) = lines.iter().position(|line| !line.trim().is_empty()) else {
+        return Message::default();
+    };
+    let header = lines[header_index].trim().to_string();
+    let rest = &lines[header_index + 1..];
+
+    // The footer block is the last paragraph, if every line is a footer or continuation;
+    // like every paragraph after the header it must follow a blank line
+    let last_paragraph_start = rest
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map_or(rest.len(), |index| index + 1);
+    let candidate = &rest[last_paragraph_start..];
+    let footers = parse_footers(candidate, header_index + 1 + last_paragraph_start);
+    let body_lines = match &footers {
+        Some(_) => &rest[..last_paragraph_start],
+        None => rest,
+    };
+
+    Message {
+        header,
+        body: body_lines.join("\n").trim().to_string(),
+        footers: footers.unwrap_or_default(),
+    }
+}
+
+/// Footers of a paragraph starting at 0-based line `offset`, or `None` if the
+/// paragraph is not a footer block
+fn parse_footers(paragraph: &[&str], offset: usize) -> Option<Vec<Footer>> {
+    if paragraph.is_empty() {
+        return None;
+    }
+    let mut footers: Vec<Footer> = Vec::new();
+    for (index, line) in paragraph.iter().enumerate() {
+        if let Some(captures) = footer_line().captures(line) {
+            footers.push(Footer {
+                token: captures["token"].to_string(),
+                separator: captures["separator"].to_string(),
+                value: captures["value"].trim().to_string(),
+                line: offset + index + 1,
+            });
+        } else if line.starts_with(char::is_whitespace) && !footers.is_empty() {
+            let last = footers.last_mut().expect("checked non-empty");
+            last.value.push('\n');
+            last.value.push_str(line.trim());
+        } else {
+            return None;
+        }
+    }
+    Some(footers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_header_body_and_footers() {
+        let message = parse(
+            "feat: add x\n\nFirst paragraph.\n\nSecond: not a footer block\nbecause of this line\n\nRefs: #12\nCloses #34\nBREAKING CHANGE: the api\n  changed\n",
+        );
+        assert_eq!(message.header, "feat: add x");
+        assert_eq!(
+            message.body,
+            "First paragraph.\n\nSecond: not a footer block\nbecause of this line"
+        );
+        let footers: Vec<_> = message
+            .footers
+            .iter()
+            .map(|f| (f.token.as_str(), f.value.as_str(), f.line))
+            .collect();
+        assert_eq!(
+            footers,
+            vec![
+                ("Refs", "#12", 8),
+                ("Closes", "34", 9),
+                ("BREAKING CHANGE", "the api\nchanged", 10),
+            ]
+        );
+        assert_eq!(message.footers_named("refs").count(), 1);
+        assert_eq!(message.footers[1].text(), "#34");
+    }
+
+    #[test]
+    fn appends_trailer_above_comments() {
+        assert_eq!(
+            append_trailer("feat: x\n\n; comment\n", "Signed-off-by: A <a@b.c>", ";"),
+            "feat: x\n\nSigned-off-by: A <a@b.c>\n\n; comment\n"
+        );
+        assert_eq!(
+            append_trailer("feat: x\n\nRefs: #1\n", "Signed-off-by: A <a@b.c>", "#"),
+            "feat: x\n\nRefs: #1\nSigned-off-by: A <a@b.c>\n"
+        );
+    }
+
+    #[test]
+    fn appends_trailer_after_body_ending_in_footer_like_line() {
+        let fixed = append_trailer(
+            "feat: x\n\nSee the docs\nNote: this is tricky\n",
+            "Signed-off-by: A <a@b.c>",
+            "#",
+        );
+        assert_eq!(
+            fixed,
+            "feat: x\n\nSee the docs\nNote: this is tricky\n\nSigned-off-by: A <a@b.c>\n"
+        );
+        let message = parse(&fixed);
+        assert_eq!(message.body, "See the docs\nNote: this is tricky");
+        assert_eq!(message.footers_named("signed-off-by").count(), 1);
+    }
+
+    #[test]
+    fn body_without_footers() {
+        let message = parse("fix: y\n\nJust a body.\n");
+        assert_eq!(message.body, "Just a body.");
+        assert!(message.footers.is_empty());
+        assert_eq!(parse("fix: y").body, "");
+
+        let message = parse("feat: x\nRefs: #1\n");
+        assert_eq!(message.body, "Refs: #1");
+        assert!(message.footers.is_empty());
+    }
+}
@@ -0,0 +1,337 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::content::Severity;
+use crate::custom_rules::{Target, Violation};
+use crate::message::{Footer, Message};
+use crate::ParsedHeader;
+
+/// Version of the JSON protocol spoken with plugins; see "Plugins" in the README
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// How long a plugin may run unless `timeout_ms` says otherwise
+pub const DEFAULT_TIMEOUT_MS: u64 = 5000;
+
+/// An external rule executable, configured as a `[[plugins]]` entry
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PluginConfig {
+    /// Name reported with the plugin's diagnostics
+    pub id: String,
+
+    /// Executable to run; a relative path containing `/` is resolved from the
+    /// repository root, a bare name is looked up on `PATH`
+    pub command: String,
+
+    /// Arguments passed to the executable
+    pub args: Vec<String>,
+
+    /// Milliseconds the plugin may run before it is killed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// A plugin ready to run
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    pub id: String,
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    pub timeout: Duration,
+    /// Directory the plugin runs in
+    pub dir: PathBuf,
+}
+
+/// The `[[plugins]]` entries, with relative commands resolved against `root`
+pub fn load(configs: &[PluginConfig], root: &Path) -> Result<Vec<Plugin>> {
+    let mut plugins: Vec<Plugin> = Vec::new();
+    for config in configs {
+        let id = config.id.trim();
+        if id.is_empty() {
+            bail!("plugins entries need an `id`");
+        }
+        if plugins.iter().any(|plugin| plugin.id == id) {
+            bail!("duplicate plugin id `{id}`");
+        }
+        if config.command.trim().is_empty() {
+            bail!("plugin `{id}` needs a `command`");
+        }
+        let command = Path::new(&config.command);
+        let program = if command.is_relative() && command.components().count() > 1 {
+            root.join(command)
+        } else {
+            command.to_path_buf()
+        };
+        plugins.push(Plugin {
+            id: id.to_string(),
+            program,
+            args: config.args.clone(),
+            timeout: Duration::from_millis(config.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS)),
+            dir: root.to_path_buf(),
+        });
+    }
+    Ok(plugins)
+}
+
+/// What a plugin receives on stdin
+#[derive(Debug, Serialize)]
+pub struct Request<'a> {
+    pub protocol: u32,
+    pub commit: CommitInput<'a>,
+}
+
+/// The parsed commit sent to plugins
+#[derive(Debug, Serialize)]
+pub struct CommitInput<'a> {
+    /// Cleaned-up message
+    pub message: &'a str,
+    pub header: &'a str,
+    #[serde(rename = "type")]
+    pub commit_type: &'a str,
+    pub scope: Option<&'a str>,
+    pub breaking: bool,
+    pub subject: &'a str,
+    pub body: &'a str,
+    pub footers: &'a [Footer],
+}
+
+impl<'a> Request<'a> {
+    pub fn new(raw: &'a str, header: &ParsedHeader<'a>, message: &'a Message) -> Self {
+        Self {
+            protocol: PROTOCOL_VERSION,
+            commit: CommitInput {
+                message: raw,
+                header: &message.header,
+                commit_type: header.commit_type,
+                scope: header.scope,
+                breaking: header.breaking,
+                subject: header.subject,
+                body: &message.body,
+                footers: &message.footers,
+            },
+        }
+    }
+}
+
+/// What a plugin prints on stdout
+#[derive(Debug, Deserialize)]
+struct Response {
+    protocol: u32,
+    #[serde(default)]
+    diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Diagnostic {
+    message: String,
+    #[serde(default)]
+    severity: Severity,
+    rule: Option<String>,
+    target: Option<Target>,
+}
+
+impl Plugin {
+    /// Run the plugin on `request`; the error describes why it could not be run or
+    /// did not answer as the protocol requires
+    pub fn run(&self, request: &Request) -> Result<Vec<Violation>, String> {
+        let input = serde_json::to_vec(request).map_err(|err| err.to_string())?;
+        let stdout = self.execute(input)?;
+        let response: Response =
+            serde_json::from_slice(&stdout).map_err(|err| format!("invalid response: {err}"))?;
+        if response.protocol != PROTOCOL_VERSION {
+            return Err(format!(
+                "speaks protocol {}, cc-check speaks {PROTOCOL_VERSION}",
+                response.protocol
+            ));
+        }
+
+        Ok(response
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| Violation {
+                rule: match diagnostic.rule {
+                    Some(rule) => format!("{}/{rule}", self.id),
+                    None => self.id.clone(),
+                },
+                message: diagnostic.message,
+                severity: diagnostic.severity,
+                target: diagnostic.target.unwrap_or(Target::Message),
+            })
+            .collect())
+    }
+
+    /// Run the executable with `input` on stdin, returning its stdout
+    fn execute(&self, input: Vec<u8>) -> Result<Vec<u8>, String> {
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .current_dir(&self.dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("cannot run {}: {err}", self.program.display()))?;
+
+        // Feed and drain the pipes on threads, so a chatty plugin can't block on them.
+        // The threads are never joined: a background process the plugin started may
+        // hold the pipes open long after the plugin itself exits.
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        thread::spawn(move || {
+            // A plugin may exit without reading its input
+            let _ = stdin.write_all(&input);
+        });
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let (stdout_tx, stdout_rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = stdout_tx.send(stdout.read_to_end(&mut buffer).map(|_| buffer));
+        });
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let (stderr_tx, stderr_rx) = mpsc::channel();
+        thread::spawn(move || {
+            let mut buffer = String::new();
+            let _ = stderr.read_to_string(&mut buffer);
+            let _ = stderr_tx.send(buffer);
+        });
+
+        let deadline = Instant::now() + self.timeout;
+        let timed_out = || format!("timed out after {}ms", self.timeout.as_millis());
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(timed_out());
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(10)),
+                Err(err) => return Err(err.to_string()),
+            }
+        };
+
+        // The output is complete once the pipes close, which must happen in time too
+        let remaining = || deadline.saturating_duration_since(Instant::now());
+        let stdout = match stdout_rx.recv_timeout(remaining()) {
+            Ok(read) => read.map_err(|err| err.to_string())?,
+            Err(RecvTimeoutError::Timeout) => return Err(timed_out()),
+            Err(RecvTimeoutError::Disconnected) => return Err("failed to read output".to_string()),
+        };
+        let stderr = match stderr_rx.recv_timeout(remaining()) {
+            Ok(stderr) => stderr,
+            Err(RecvTimeoutError::Timeout) => return Err(timed_out()),
+            Err(RecvTimeoutError::Disconnected) => String::new(),
+        };
+        if !status.success() {
+            let detail = stderr.trim();
+            return Err(if detail.is_empty() {
+                format!("exited with {status}")
+            } else {
+                format!("exited with {status}: {detail}")
+            });
+        }
+        Ok(stdout)
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::{message, HeaderRules};
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    /// A plugin running `script` with sh, from a temporary directory
+    fn stub(script: &str, timeout_ms: Option<u64>) -> (TempDir, Plugin) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("plugin.sh");
+        std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let config = PluginConfig {
+            id: "stub".to_string(),
+            command: "./plugin.sh".to_string(),
+            timeout_ms,
+            ..PluginConfig::default()
+        };
+        let plugin = load(&[config], dir.path()).unwrap().remove(0);
+        (dir, plugin)
+    }
+
+    fn run(plugin: &Plugin, text: &str) -> Result<Vec<Violation>, String> {
+        let rules = HeaderRules::new(&[], 0, false);
+        let parsed = message::parse(text);
+        let header = rules.parse(&parsed.header).unwrap();
+        plugin.run(&Request::new(text, &header, &parsed))
+    }
+
+    #[test]
+    fn sends_the_commit_and_reads_diagnostics() {
+        let (_dir, plugin) = stub(
+            r#"input=$(cat)
+case "$input" in
+  *'"protocol":1,"commit":{'*'"type":"fix"'*'"footers":[{"token":"Refs"'*)
+    echo '{"protocol":1,"diagnostics":[{"rule":"r","message":"m","severity":"warning","target":"body"}]}' ;;
+  *) echo '{"protocol":1,"diagnostics":[]}' ;;
+esac"#,
+            None,
+        );
+        assert_eq!(run(&plugin, "feat: x\n"), Ok(Vec::new()));
+        assert_eq!(
+            run(&plugin, "fix: x\n\nRefs: #1\n"),
+            Ok(vec![Violation {
+                rule: "stub/r".to_string(),
+                message: "m".to_string(),
+                severity: Severity::Warning,
+                target: Target::Body,
+            }])
+        );
+    }
+
+    #[test]
+    fn protocol_errors() {
+        let (_dir, plugin) = stub("echo '{\"protocol\":2}'", None);
+        assert_eq!(
+            run(&plugin, "feat: x\n"),
+            Err("speaks protocol 2, cc-check speaks 1".to_string())
+        );
+
+        let (_dir, plugin) = stub("echo not json", None);
+        assert!(run(&plugin, "feat: x\n")
+            .unwrap_err()
+            .starts_with("invalid response"));
+
+        let (_dir, plugin) = stub("echo oops >&2; exit 3", None);
+        assert_eq!(
+            run(&plugin, "feat: x\n"),
+            Err("exited with exit status: 3: oops".to_string())
+        );
+    }
+
+    #[test]
+    fn slow_plugins_time_out() {
+        let (_dir, plugin) = stub("sleep 5", Some(100));
+        assert_eq!(
+            run(&plugin, "feat: x\n"),
+            Err("timed out after 100ms".to_string())
+        );
+    }
+
+    #[test]
+    fn background_processes_holding_the_pipes_time_out() {
+        let (_dir, plugin) = stub(
+            "sleep 60 &\necho '{\"protocol\":1,\"diagnostics\":[]}'",
+            Some(300),
+        );
+        let started = Instant::now();
+        assert_eq!(
+            run(&plugin, "feat: x\n"),
+            Err("timed out after 300ms".to_string())
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{bail, Result};
-use cc_check::find_repo_root;
+use cc_check::find_crate_root;
 use std::env;
 use std::process::{Command, ExitStatus, Stdio};
 
@@ -20,7 +20,7 @@ fn main() -> Result<()> {
         args.push("check".to_string());
     }
 
-    let repo_root = find_repo_root()?;
+    let repo_root = find_crate_root()?;
 
     // Try release binary first
     let release_bin = repo_root.join("target/release/cc-check");
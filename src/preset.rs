@@ -0,0 +1,188 @@
+use crate::config::Config;
+use crate::{SubjectCase, DEFAULT_HEADER_PATTERN, DEFAULT_TYPES};
+
+/// A built-in commit message convention
+#[derive(Debug, Clone, Copy)]
+pub struct Preset {
+    /// Name used in `preset = "..."`, `--preset` and `extends = ["preset:..."]`
+    pub name: &'static str,
+    /// One-line description of the convention
+    pub description: &'static str,
+    /// Allowed types; empty allows any type
+    pub types: &'static [&'static str],
+    /// Header pattern with named groups `type`, `subject` and optionally `scope`
+    pub header_pattern: &'static str,
+    /// Case rule for the subject
+    pub subject_case: SubjectCase,
+    /// Maximum subject length
+    pub max_subject: usize,
+    /// Disallow trailing period in subject
+    pub no_trailing_period: bool,
+}
+
+/// All built-in presets
+pub const PRESETS: &[Preset] = &[
+    // Reference: https://github.com/angular/angular/blob/main/CONTRIBUTING.md#commit
+    Preset {
+        name: "angular",
+        description: "Angular commit message guidelines",
+        types: DEFAULT_TYPES,
+        header_pattern: DEFAULT_HEADER_PATTERN,
+        subject_case: SubjectCase::Lower,
+        max_subject: 72,
+        no_trailing_period: true,
+    },
+    // Reference: https://www.conventionalcommits.org/ (as configured by @commitlint/config-conventional)
+    Preset {
+        name: "conventional",
+        description: "Conventional Commits with the commitlint config-conventional rules",
+        types: DEFAULT_TYPES,
+        header_pattern: DEFAULT_HEADER_PATTERN,
+        subject_case: SubjectCase::Lower,
+        max_subject: 100,
+        no_trailing_period: true,
+    },
+    // Reference: https://github.com/atom/atom/blob/master/CONTRIBUTING.md#git-commit-messages
+    Preset {
+        name: "atom",
+        description: "Atom emoji-prefixed messages, e.g. ':bug: Fix crash on save'",
+        types: &[
+            ":art:",
+            ":racehorse:",
+            ":non-potable_water:",
+            ":memo:",
+            ":penguin:",
+            ":apple:",
+            ":checkered_flag:",
+            ":bug:",
+            ":fire:",
+            ":green_heart:",
+            ":white_check_mark:",
+            ":lock:",
+            ":arrow_up:",
+            ":arrow_down:",
+            ":shirt:",
+        ],
+        header_pattern: r"^(?P<type>:[a-z0-9_+-]+:) (?P<subject>.+)$",
+        subject_case: SubjectCase::Any,
+        max_subject: 72,
+        no_trailing_period: false,
+    },
+    // Reference: https://github.com/emberjs/ember.js/blob/main/CONTRIBUTING.md#commit-tagging
+    Preset {
+        name: "ember",
+        description: "Ember tagged messages, e.g. '[BUGFIX beta] Fix observer'",
+        types: &["BUGFIX", "CLEANUP", "DOC", "FEATURE", "SECURITY"],
+        header_pattern: r"^\[(?P<type>[A-Z]+)(?: (?P<scope>[^\]]+))?\] (?P<subject>.+)$",
+        subject_case: SubjectCase::Any,
+        max_subject: 72,
+        no_trailing_period: false,
+    },
+    // Reference: https://eslint.org/docs/latest/contribute/pull-requests#commit-messages
+    Preset {
+        name: "eslint",
+        description: "ESLint tagged messages, e.g. 'Fix: Semi-colon insertion'",
+        types: &[
+            "Breaking", "Build", "Chore", "Docs", "Fix", "New", "Update", "Upgrade",
+        ],
+        header_pattern: r"^(?P<type>[A-Z][a-z]+): (?P<subject>.+)$",
+        subject_case: SubjectCase::Any,
+        max_subject: 72,
+        no_trailing_period: false,
+    },
+    // Reference: https://contribute.jquery.org/commits-and-pull-requests/#commit-guidelines
+    Preset {
+        name: "jquery",
+        description: "jQuery component-prefixed messages, e.g. 'Ajax: Fix timeout handling'",
+        types: &[],
+        header_pattern: r"^(?P<type>[^:\s][^:]*): (?P<subject>.+)$",
+        subject_case: SubjectCase::Any,
+        max_subject: 72,
+        no_trailing_period: false,
+    },
+];
+
+/// Look up a built-in preset by name
+pub fn find(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
+
+/// Names of all built-in presets, for error messages
+pub fn names() -> Vec<&'static str> {
+    PRESETS.iter().map(|preset| preset.name).collect()
+}
+
+impl Preset {
+    /// The preset as a config layer
+    pub fn to_config(&self) -> Config {
+        Config {
+            preset: Some(self.name.to_string()),
+            header_pattern: Some(self.header_pattern.to_string()),
+            subject_case: Some(self.subject_case),
+            max_subject: Some(self.max_subject),
+            no_trailing_period: Some(self.no_trailing_period),
+            ..Config::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{validate_header_with_rules, HeaderRules, LengthUnit};
+    use regex::Regex;
+
+    fn rules(preset: &Preset) -> HeaderRules {
+        HeaderRules {
+            pattern: Regex::new(preset.header_pattern).unwrap(),
+            allowed_types: preset.types.iter().map(|t| t.to_string()).collect(),
+            disallowed_types: Vec::new(),
+            max_subject: preset.max_subject,
+            min_subject: 0,
+            max_header: 0,
+            length_unit: LengthUnit::Chars,
+            no_trailing_period: preset.no_trailing_period,
+            subject_case: preset.subject_case,
+        }
+    }
+
+    #[test]
+    fn every_preset_pattern_compiles_with_required_groups() {
+        for preset in PRESETS {
+            let pattern = Regex::new(preset.header_pattern).unwrap();
+            let groups: Vec<_> = pattern.capture_names().flatten().collect();
+            assert!(groups.contains(&"type"), "{} lacks type group", preset.name);
+            assert!(groups.contains(&"subject"), "{} lacks subject", preset.name);
+        }
+    }
+
+    #[test]
+    fn presets_accept_their_own_examples() {
+        let examples = [
+            ("angular", "feat(core): add signal inputs"),
+            ("conventional", "fix!: drop node 14 support"),
+            ("atom", ":bug: Fix crash when saving"),
+            ("ember", "[BUGFIX beta] Fix observer teardown"),
+            ("eslint", "Fix: Semi-colon insertion (fixes #1234)"),
+            ("jquery", "Ajax: Fix timeout handling"),
+        ];
+        for (name, header) in examples {
+            let preset = find(name).unwrap();
+            assert!(
+                validate_header_with_rules(header, &rules(preset)).is_ok(),
+                "{name} should accept {header:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn angular_rejects_capitalized_subject() {
+        let preset = find("angular").unwrap();
+        assert!(validate_header_with_rules("feat: Add x", &rules(preset)).is_err());
+    }
+
+    #[test]
+    fn unknown_preset_is_not_found() {
+        assert!(find("gitmoji").is_none());
+    }
+}
@@ -0,0 +1,228 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+
+/// Default tracker patterns: Jira-style keys, GitHub issues (`#123`, `GH-123`) and
+/// GitLab merge requests (`!45`)
+pub const DEFAULT_PATTERNS: &[&str] = &[r"\b[A-Z][A-Z0-9_]+-\d+\b", r"#\d+\b", r"!\d+\b"];
+
+/// Footers that carry issue references by default
+pub const DEFAULT_FOOTER_TOKENS: &[&str] = &["Refs", "Closes", "Fixes", "Resolves", "See"];
+
+/// Part of a commit message a reference was found in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Location {
+    Subject,
+    Body,
+    Footer,
+}
+
+/// Issue-reference settings, configured under `[references]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReferenceConfig {
+    /// Regexes matching an issue reference, e.g. `PROJ-\d+`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patterns: Option<Vec<String>>,
+    /// Commit types that must reference an issue; "*" for every type
+    pub required_for: Vec<String>,
+    /// Where references are looked for: "subject", "body" and/or "footer"; empty to
+    /// turn extraction off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locations: Option<Vec<Location>>,
+    /// Footer tokens whose values are searched, e.g. "Refs" or "Closes"; empty to
+    /// search every footer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer_tokens: Option<Vec<String>>,
+}
+
+impl ReferenceConfig {
+    /// The built-in defaults, with every option set
+    pub fn defaults() -> Self {
+        Self {
+            patterns: Some(DEFAULT_PATTERNS.iter().map(|p| p.to_string()).collect()),
+            required_for: Vec::new(),
+            locations: Some(vec![Location::Subject, Location::Body, Location::Footer]),
+            footer_tokens: Some(
+                DEFAULT_FOOTER_TOKENS
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Compile the configured patterns
+    pub fn rules(&self) -> Result<ReferenceRules> {
+        let defaults = Self::defaults();
+        let patterns = self
+            .patterns
+            .as_ref()
+            .or(defaults.patterns.as_ref())
+            .into_iter()
+            .flatten()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| format!("invalid references.patterns entry `{pattern}`"))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(ReferenceRules {
+            patterns,
+            required_for: self.required_for.clone(),
+            locations: self
+                .locations
+                .clone()
+                .or(defaults.locations)
+                .unwrap_or_default(),
+            footer_tokens: self
+                .footer_tokens
+                .clone()
+                .or(defaults.footer_tokens)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Compiled issue-reference settings
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceRules {
+    pub patterns: Vec<Regex>,
+    pub required_for: Vec<String>,
+    pub locations: Vec<Location>,
+    pub footer_tokens: Vec<String>,
+}
+
+impl ReferenceRules {
+    /// Whether commits of `commit_type` must reference an issue
+    pub fn required(&self, commit_type: &str) -> bool {
+        self.required_for
+            .iter()
+            .any(|required| required == "*" || required == commit_type)
+    }
+
+    /// References in `message`, whose header has the given `subject`, in message order
+    pub fn extract(&self, message: &Message, subject: &str) -> Vec<Reference> {
+        let mut references: Vec<Reference> = Vec::new();
+        let mut add = |text: &str, location: Location| {
+            let mut found: Vec<(usize, &str)> = self
+                .patterns
+                .iter()
+                .flat_map(|pattern| pattern.find_iter(text))
+                .map(|m| (m.start(), m.as_str()))
+                .collect();
+            found.sort();
+            for (_, value) in found {
+                if !references.iter().any(|r| r.value == value) {
+                    references.push(Reference {
+                        value: value.to_string(),
+                        location,
+                    });
+                }
+            }
+        };
+
+        for location in &self.locations {
+            match location {
+                Location::Subject => add(subject, Location::Subject),
+                Location::Body => add(&message.body, Location::Body),
+                Location::Footer => {
+                    for footer in &message.footers {
+                        let searched = self.footer_tokens.is_empty()
+                            || self
+                                .footer_tokens
+                                .iter()
+                                .any(|token| token.eq_ignore_ascii_case(&footer.token));
+                        if searched {
+                            add(&footer.text(), Location::Footer);
+                        }
+                    }
+                }
+            }
+        }
+        references
+    }
+}
+
+/// An issue reference found in a commit message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Reference {
+    /// The reference as written, e.g. `PROJ-123` or `#12`
+    pub value: String,
+    /// Where it was found
+    pub location: Location,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message;
+
+    fn extract(config: &ReferenceConfig, text: &str) -> Vec<(String, Location)> {
+        let parsed = message::parse(text);
+        let subject = parsed.header.split_once(": ").map_or("", |(_, s)| s);
+        config
+            .rules()
+            .unwrap()
+            .extract(&parsed, subject)
+            .into_iter()
+            .map(|r| (r.value, r.location))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_default_tracker_formats() {
+        let found = extract(
+            &ReferenceConfig::defaults(),
+            "feat: add login (PROJ-12)\n\nSee GH-7 and !45.\n\nCloses #34\nReviewed-by: #99\n",
+        );
+        assert_eq!(
+            found,
+            vec![
+                ("PROJ-12".to_string(), Location::Subject),
+                ("GH-7".to_string(), Location::Body),
+                ("!45".to_string(), Location::Body),
+                ("#34".to_string(), Location::Footer),
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_patterns_and_locations() {
+        let config = ReferenceConfig {
+            patterns: Some(vec![r"\bABC-\d+\b".to_string()]),
+            locations: Some(vec![Location::Footer]),
+            footer_tokens: Some(vec!["Refs".to_string()]),
+            ..ReferenceConfig::default()
+        };
+        assert!(extract(&config, "fix: ABC-1 in subject\n").is_empty());
+        assert_eq!(
+            extract(&config, "fix: y\n\nRefs: ABC-2, XYZ-3\n"),
+            vec![("ABC-2".to_string(), Location::Footer)]
+        );
+    }
+
+    #[test]
+    fn empty_locations_turn_extraction_off() {
+        let config = ReferenceConfig {
+            locations: Some(Vec::new()),
+            ..ReferenceConfig::defaults()
+        };
+        assert!(extract(&config, "fix: PROJ-1\n\nRefs: #2\n").is_empty());
+    }
+
+    #[test]
+    fn required_for_types_or_wildcard() {
+        let mut rules = ReferenceRules {
+            required_for: vec!["feat".to_string()],
+            ..ReferenceRules::default()
+        };
+        assert!(rules.required("feat"));
+        assert!(!rules.required("docs"));
+        rules.required_for = vec!["*".to_string()];
+        assert!(rules.required("docs"));
+    }
+}
@@ -0,0 +1,313 @@
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Files that mark the root of a package (crate, npm package or Python project)
+pub const PACKAGE_MARKERS: &[&str] = &["Cargo.toml", "package.json", "pyproject.toml"];
+
+/// Locations of the repository and package enclosing a directory
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoPaths {
+    /// Top level of the git working tree
+    pub git_root: Option<PathBuf>,
+    /// The git directory; differs from `<git_root>/.git` for worktrees and submodules
+    pub git_dir: Option<PathBuf>,
+    /// Nearest directory containing one of [`PACKAGE_MARKERS`]
+    pub package_root: Option<PathBuf>,
+}
+
+impl RepoPaths {
+    /// Discover the repository around `start`, asking git first and falling back to
+    /// looking for a `.git` directory or file when git is unavailable or fails (e.g. a
+    /// `.git` it can't read)
+    pub fn discover(start: &Path) -> Self {
+        let (git_root, git_dir) = match git_rev_parse(start, "--show-toplevel") {
+            Some(root) => {
+                let git_dir =
+                    git_rev_parse(start, "--absolute-git-dir").or_else(|| resolve_dot_git(&root));
+                (Some(root), git_dir)
+            }
+            None => match find_dot_git(start) {
+                Some(root) => {
+                    let git_dir = resolve_dot_git(&root);
+                    (Some(root), git_dir)
+                }
+                None => (None, None),
+            },
+        };
+
+        let package_root = start
+            .ancestors()
+            .find(|dir| {
+                PACKAGE_MARKERS
+                    .iter()
+                    .any(|marker| dir.join(marker).is_file())
+            })
+            .map(Path::to_path_buf);
+
+        Self {
+            git_root,
+            git_dir,
+            package_root,
+        }
+    }
+
+    /// The directory config discovery stops at: the git root, else the package root
+    pub fn boundary(&self) -> Option<&Path> {
+        self.git_root.as_deref().or(self.package_root.as_deref())
+    }
+}
+
+/// Paths staged for the next commit, relative to the git root
+///
+/// In a commit-msg hook git points `GIT_INDEX_FILE` at the index being committed, so
+/// this also covers `git commit -a` and `git commit <paths>`.
+pub fn staged_paths(git_root: &Path) -> Vec<PathBuf> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--name-only", "-z"])
+        .current_dir(git_root)
+        .output();
+    match output {
+        Ok(output) if output.status.success() => output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| PathBuf::from(String::from_utf8_lossy(path).into_owned()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Deepest directory containing every one of the (relative) `paths`; empty for the
+/// repository root, `None` if there are no paths
+pub fn common_directory(paths: &[PathBuf]) -> Option<PathBuf> {
+    let mut dirs = paths
+        .iter()
+        .map(|path| path.parent().unwrap_or(Path::new("")));
+    let first = dirs.next()?;
+    let mut common: Vec<_> = first.components().collect();
+    for dir in dirs {
+        let shared = common
+            .iter()
+            .zip(dir.components())
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        common.truncate(shared);
+    }
+    Some(common.iter().collect())
+}
+
+/// A commit read from the repository history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Commit {
+    /// Full commit hash
+    pub sha: String,
+    /// Author as `Name <email>`
+    pub author: String,
+    /// Raw commit message, as stored by git
+    pub message: String,
+}
+
+/// Commits in a revision range such as `origin/main..HEAD`, oldest first
+pub fn commits_in_range(dir: &Path, range: &str) -> Result<Vec<Commit>> {
+    let output = Command::new("git")
+        .args([
+            "log",
+            "--reverse",
+            "--format=%H%x00%an <%ae>%x00%B%x00",
+            range,
+            "--",
+        ])
+        .current_dir(dir)
+        .output()
+        .context("failed to run git log")?;
+    if !output.status.success() {
+        bail!(
+            "git log {range} failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split('\0');
+    let mut commits = Vec::new();
+    while let (Some(sha), Some(author), Some(message)) =
+        (fields.next(), fields.next(), fields.next())
+    {
+        commits.push(Commit {
+            sha: sha.trim().to_string(),
+            author: author.to_string(),
+            message: message.to_string(),
+        });
+    }
+    Ok(commits)
+}
+
+/// Message of the commit `rev` resolves to, or `None` if it is not a commit here
+pub fn commit_message(dir: &Path, rev: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args([
+            "log",
+            "-1",
+            "--format=%B",
+            &format!("{rev}^{{commit}}"),
+            "--",
+        ])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Value of a git config key as seen from `dir`, e.g. `core.commentChar`
+pub fn git_config(dir: &Path, key: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["config", "--get", key])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let value = String::from_utf8(output.stdout).ok()?;
+    let value = value.trim_end_matches(['\n', '\r']);
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+/// Identity git would use in `dir` as `Name <email>`, for `GIT_AUTHOR_IDENT` or
+/// `GIT_COMMITTER_IDENT`; honours the `GIT_AUTHOR_*`/`GIT_COMMITTER_*` variables git
+/// sets for hooks
+pub fn git_ident(dir: &Path, var: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["var", var])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let ident = String::from_utf8(output.stdout).ok()?;
+    // Drop the trailing `<timestamp> <timezone>`
+    let end = ident.rfind('>')?;
+    Some(ident[..=end].to_string())
+}
+
+fn git_rev_parse(start: &Path, flag: &str) -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", flag])
+        .current_dir(start)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let path = String::from_utf8(output.stdout).ok()?;
+    let path = path.trim();
+    if path.is_empty() {
+        None
+    } else {
+        Some(PathBuf::from(path))
+    }
+}
+
+/// Nearest ancestor of `start` containing a `.git` directory or file
+fn find_dot_git(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Resolve `<root>/.git` to the git directory, following the `gitdir: <path>` pointer
+/// that worktrees and submodules use instead of a directory
+fn resolve_dot_git(root: &Path) -> Option<PathBuf> {
+    let dot_git = root.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+    let contents = std::fs::read_to_string(&dot_git).ok()?;
+    let target = contents.lines().next()?.strip_prefix("gitdir:")?.trim();
+    Some(root.join(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn follows_gitdir_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let worktree = temp_dir.path().join("worktree");
+        std::fs::create_dir(&worktree).unwrap();
+        std::fs::write(worktree.join(".git"), "gitdir: ../main/.git/worktrees/wt\n").unwrap();
+
+        assert_eq!(
+            resolve_dot_git(&worktree),
+            Some(worktree.join("../main/.git/worktrees/wt"))
+        );
+    }
+
+    #[test]
+    fn discovers_git_root_above_nested_packages() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+        let nested = temp_dir.path().join("crates").join("inner");
+        std::fs::create_dir_all(nested.join("src")).unwrap();
+        std::fs::write(nested.join("Cargo.toml"), "[package]\nname = \"inner\"").unwrap();
+
+        let paths = RepoPaths::discover(&nested.join("src"));
+        assert_eq!(paths.git_root.as_deref(), Some(temp_dir.path()));
+        assert_eq!(paths.git_dir, Some(temp_dir.path().join(".git")));
+        assert_eq!(paths.package_root.as_deref(), Some(nested.as_path()));
+        assert_eq!(paths.boundary(), Some(temp_dir.path()));
+    }
+
+    #[test]
+    fn common_directory_of_paths() {
+        let paths = |list: &[&str]| list.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        assert_eq!(common_directory(&[]), None);
+        assert_eq!(
+            common_directory(&paths(&[
+                "packages/api/src/a.rs",
+                "packages/api/Cargo.toml"
+            ])),
+            Some(PathBuf::from("packages/api"))
+        );
+        assert_eq!(
+            common_directory(&paths(&["packages/api/src/a.rs", "packages/web/index.ts"])),
+            Some(PathBuf::from("packages"))
+        );
+        assert_eq!(
+            common_directory(&paths(&["packages/api/src/a.rs", "README.md"])),
+            Some(PathBuf::new())
+        );
+    }
+
+    #[test]
+    fn uses_git_for_real_repositories() {
+        let temp_dir = TempDir::new().unwrap();
+        let initialized = Command::new("git")
+            .args(["init", "-q"])
+            .current_dir(temp_dir.path())
+            .status()
+            .is_ok_and(|status| status.success());
+        if !initialized {
+            // git is not available in this environment
+            return;
+        }
+        let sub_dir = temp_dir.path().join("a").join("b");
+        std::fs::create_dir_all(&sub_dir).unwrap();
+
+        let paths = RepoPaths::discover(&sub_dir);
+        let expected = std::fs::canonicalize(temp_dir.path()).unwrap();
+        let actual = std::fs::canonicalize(paths.git_root.unwrap()).unwrap();
+        assert_eq!(actual, expected);
+        assert!(paths.git_dir.unwrap().ends_with(".git"));
+        assert_eq!(paths.package_root, None);
+    }
+}
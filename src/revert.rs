@@ -0,0 +1,67 @@
+use crate::git_message::reverted_commit;
+use crate::is_commit_hash;
+
+/// Commit hashes a revert message points at: `Refs:` footers (comma or space
+/// separated) and git's `This reverts commit <sha>.` body line, in order of appearance
+pub fn reverted_commits(message: &str) -> Vec<String> {
+    let mut shas: Vec<String> = Vec::new();
+    for line in message.lines().map(str::trim) {
+        if let Some(sha) = reverted_commit(line) {
+            shas.push(sha.to_string());
+            continue;
+        }
+        let Some((token, value)) = line.split_once(':') else {
+            continue;
+        };
+        if token.eq_ignore_ascii_case("refs") || token.eq_ignore_ascii_case("ref") {
+            shas.extend(
+                value
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|candidate| is_commit_hash(candidate))
+                    .map(String::from),
+            );
+        }
+    }
+    let mut unique: Vec<String> = Vec::with_capacity(shas.len());
+    for sha in shas {
+        if !unique.contains(&sha) {
+            unique.push(sha);
+        }
+    }
+    unique
+}
+
+/// Whether a revert subject names the reverted commit's header, either verbatim,
+/// quoted (`revert: "feat: add x"`) or as just its subject (`revert: add x`)
+pub fn subject_matches(
+    revert_subject: &str,
+    reverted_header: &str,
+    reverted_subject: &str,
+) -> bool {
+    let subject = revert_subject.trim().trim_matches('"');
+    subject == reverted_header || subject == reverted_subject
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_refs_footers_and_git_revert_lines() {
+        let message =
+            "revert: add x\n\nThis reverts commit 1a2b3c4d5e.\n\nRefs: 2b3c4d5, 3c4d5e6 #12\n";
+        assert_eq!(
+            reverted_commits(message),
+            vec!["1a2b3c4d5e", "2b3c4d5", "3c4d5e6"]
+        );
+        assert!(reverted_commits("revert: add x\n\nRefs: #12\n").is_empty());
+    }
+
+    #[test]
+    fn matches_header_or_subject_of_reverted_commit() {
+        assert!(subject_matches("feat: add x", "feat: add x", "add x"));
+        assert!(subject_matches("\"feat: add x\"", "feat: add x", "add x"));
+        assert!(subject_matches("add x", "feat: add x", "add x"));
+        assert!(!subject_matches("add y", "feat: add x", "add x"));
+    }
+}
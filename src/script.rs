@@ -0,0 +1,296 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope, AST};
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::content::Severity;
+use crate::custom_rules::{Target, Violation};
+use crate::plugin::CommitInput;
+
+/// Where rule scripts are looked for, relative to the repository root
+pub const DEFAULT_SCRIPTS_DIR: &str = ".cc-check/rules";
+
+/// Operations a script may run per commit, which stops runaway loops
+pub const DEFAULT_MAX_OPERATIONS: u64 = 1_000_000;
+
+/// Rhai rule script settings, configured under `[scripts]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScriptConfig {
+    /// Run the `*.rhai` rule scripts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+
+    /// Directory holding the scripts, relative to the repository root
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<String>,
+
+    /// Operations a script may run per commit before it is stopped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_operations: Option<u64>,
+}
+
+impl ScriptConfig {
+    /// The built-in defaults, with every option set
+    pub fn defaults() -> Self {
+        Self {
+            enabled: Some(true),
+            dir: Some(DEFAULT_SCRIPTS_DIR.to_string()),
+            max_operations: Some(DEFAULT_MAX_OPERATIONS),
+        }
+    }
+}
+
+/// A compiled rule script
+struct Script {
+    /// File stem, reported as the rule id
+    id: String,
+    ast: AST,
+}
+
+/// A diagnostic reported by the running script
+struct Reported {
+    message: String,
+    severity: Severity,
+    target: Target,
+}
+
+/// The rule scripts of a repository, compiled once and run for every commit
+pub struct ScriptRules {
+    engine: Engine,
+    scripts: Vec<Script>,
+    /// The `repo` map scripts see
+    repo: Dynamic,
+    reported: Rc<RefCell<Vec<Reported>>>,
+}
+
+impl ScriptRules {
+    /// Compile the scripts in the configured directory under `root`; `branch` gives the
+    /// checked-out branch, and is only asked when there are scripts
+    pub fn load(
+        config: &ScriptConfig,
+        root: &Path,
+        branch: impl FnOnce() -> Option<String>,
+    ) -> Result<Self> {
+        let reported = Rc::new(RefCell::new(Vec::new()));
+        let engine = engine(
+            config.max_operations.unwrap_or(DEFAULT_MAX_OPERATIONS),
+            &reported,
+        );
+
+        let mut scripts = Vec::new();
+        if config.enabled.unwrap_or(true) {
+            let dir = root.join(config.dir.as_deref().unwrap_or(DEFAULT_SCRIPTS_DIR));
+            for path in script_files(&dir)? {
+                let source = std::fs::read_to_string(&path)
+                    .with_context(|| format!("failed to read rule script: {}", path.display()))?;
+                let ast = engine
+                    .compile(&source)
+                    .map_err(|err| anyhow::anyhow!("{}: {err}", path.display()))?;
+                let id = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                scripts.push(Script { id, ast });
+            }
+        }
+
+        let mut repo = Map::new();
+        if !scripts.is_empty() {
+            repo.insert("root".into(), root.display().to_string().into());
+            repo.insert(
+                "branch".into(),
+                branch().map_or(Dynamic::UNIT, Dynamic::from),
+            );
+        }
+
+        Ok(Self {
+            engine,
+            scripts,
+            repo: repo.into(),
+            reported,
+        })
+    }
+
+    /// Whether there are no scripts to run
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    /// Run every script on `commit`; the error names the failing script and why
+    pub fn run(&self, commit: &CommitInput) -> Result<Vec<Violation>, (String, String)> {
+        let commit =
+            rhai::serde::to_dynamic(commit).map_err(|err| ("*".to_string(), err.to_string()))?;
+
+        let mut violations = Vec::new();
+        for script in &self.scripts {
+            let mut scope = Scope::new();
+            scope.push_constant("commit", commit.clone());
+            scope.push_constant("repo", self.repo.clone());
+            let outcome = self.engine.run_ast_with_scope(&mut scope, &script.ast);
+            let reported = std::mem::take(&mut *self.reported.borrow_mut());
+            outcome.map_err(|err| (script.id.clone(), err.to_string()))?;
+
+            violations.extend(reported.into_iter().map(|reported| Violation {
+                rule: script.id.clone(),
+                message: reported.message,
+                severity: reported.severity,
+                target: reported.target,
+            }));
+        }
+        Ok(violations)
+    }
+}
+
+/// `*.rhai` files in `dir`, by name; none if the directory doesn't exist
+fn script_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read rule scripts in {}", dir.display()))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "rhai") && path.is_file())
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// An engine with the diagnostics API (`error`, `warning`) and a `matches` regex helper
+fn engine(max_operations: u64, reported: &Rc<RefCell<Vec<Reported>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(max_operations);
+    // Keep stdout clean for `--format json`
+    engine.on_print(|text| eprintln!("{text}"));
+    engine.on_debug(|text, _, _| eprintln!("{text}"));
+
+    for (name, severity) in [("error", Severity::Error), ("warning", Severity::Warning)] {
+        let sink = Rc::clone(reported);
+        engine.register_fn(name, move |message: &str| {
+            sink.borrow_mut().push(Reported {
+                message: message.to_string(),
+                severity,
+                target: Target::Message,
+            });
+        });
+        let sink = Rc::clone(reported);
+        engine.register_fn(
+            name,
+            move |message: &str, target: &str| -> Result<(), Box<EvalAltResult>> {
+                let target = Target::try_from(target.to_string())?;
+                sink.borrow_mut().push(Reported {
+                    message: message.to_string(),
+                    severity,
+                    target,
+                });
+                Ok(())
+            },
+        );
+    }
+
+    engine.register_fn(
+        "matches",
+        |text: &str, pattern: &str| -> Result<bool, Box<EvalAltResult>> {
+            let regex = Regex::new(pattern).map_err(|err| err.to_string())?;
+            Ok(regex.is_match(text))
+        },
+    );
+    engine
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin::Request;
+    use crate::{message, HeaderRules};
+    use tempfile::TempDir;
+
+    fn load(scripts: &[(&str, &str)]) -> (TempDir, ScriptRules) {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join(DEFAULT_SCRIPTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        for (name, source) in scripts {
+            std::fs::write(dir.join(name), source).unwrap();
+        }
+        let rules = ScriptRules::load(&ScriptConfig::defaults(), root.path(), || {
+            Some("main".to_string())
+        })
+        .unwrap();
+        (root, rules)
+    }
+
+    fn run(rules: &ScriptRules, text: &str) -> Result<Vec<Violation>, (String, String)> {
+        let header_rules = HeaderRules::new(&[], 0, false);
+        let parsed = message::parse(text);
+        let header = header_rules.parse(&parsed.header).unwrap();
+        rules.run(&Request::new(text, &header, &parsed).commit)
+    }
+
+    #[test]
+    fn scripts_see_the_commit_and_report_diagnostics() {
+        let (_root, rules) = load(&[
+            (
+                "fix-body.rhai",
+                r#"if commit.type == "fix" && commit.body == "" { error("fix commits need a body", "body"); }"#,
+            ),
+            (
+                "refs.rhai",
+                r#"
+                for footer in commit.footers {
+                    if footer.token == "Refs" && !matches(footer.value, "^PROJ-\\d+$") {
+                        warning(`unexpected reference ${footer.value}`, "footer:Refs");
+                    }
+                }
+                if repo.branch != "main" { error("not on main"); }
+                "#,
+            ),
+            ("notes.txt", "not a script"),
+        ]);
+        assert!(!rules.is_empty());
+        assert_eq!(run(&rules, "feat: x\n\nRefs: PROJ-1\n"), Ok(Vec::new()));
+        assert_eq!(
+            run(&rules, "fix: x\n\nRefs: #1\n"),
+            Ok(vec![
+                Violation {
+                    rule: "fix-body".to_string(),
+                    message: "fix commits need a body".to_string(),
+                    severity: Severity::Error,
+                    target: Target::Body,
+                },
+                Violation {
+                    rule: "refs".to_string(),
+                    message: "unexpected reference #1".to_string(),
+                    severity: Severity::Warning,
+                    target: Target::Footer("Refs".to_string()),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn runtime_errors_and_runaway_scripts_fail() {
+        let (_root, rules) = load(&[("bad-target.rhai", r#"error("x", "nowhere");"#)]);
+        let (id, reason) = run(&rules, "feat: x\n").unwrap_err();
+        assert_eq!(id, "bad-target");
+        assert!(reason.contains("unknown target `nowhere`"), "{reason}");
+
+        let (_root, rules) = load(&[("loop.rhai", "loop { }")]);
+        let (id, _) = run(&rules, "feat: x\n").unwrap_err();
+        assert_eq!(id, "loop");
+    }
+
+    #[test]
+    fn syntax_errors_name_the_file() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join(DEFAULT_SCRIPTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("broken.rhai"), "if {").unwrap();
+        let err = ScriptRules::load(&ScriptConfig::defaults(), root.path(), || None)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("broken.rhai"), "{err}");
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::message::Message;
+use crate::ValidationError;
+
+/// Footer token of a DCO sign-off
+pub const SIGNOFF_TOKEN: &str = "Signed-off-by";
+
+/// DCO sign-off settings, configured under `[signoff]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignoffConfig {
+    /// Require a `Signed-off-by: Name <email>` trailer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,
+
+    /// Require one of the sign-offs to be by the commit author
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub match_author: Option<bool>,
+}
+
+impl SignoffConfig {
+    /// The built-in defaults, with every option set
+    pub fn defaults() -> Self {
+        Self {
+            required: Some(false),
+            match_author: Some(false),
+        }
+    }
+}
+
+/// A `Name <email>` identity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+impl Identity {
+    /// Parse `Name <email>`, ignoring anything after the `>` such as the timestamp
+    /// `git var GIT_AUTHOR_IDENT` prints
+    pub fn parse(ident: &str) -> Option<Self> {
+        let (name, rest) = ident.split_once('<')?;
+        let (email, _) = rest.split_once('>')?;
+        let (name, email) = (name.trim(), email.trim());
+        if name.is_empty() || email.is_empty() || !email.contains('@') {
+            return None;
+        }
+        Some(Self {
+            name: name.to_string(),
+            email: email.to_string(),
+        })
+    }
+
+    /// Whether this is the same person as `other`, going by email address
+    pub fn same_as(&self, other: &Identity) -> bool {
+        self.email.eq_ignore_ascii_case(&other.email)
+    }
+}
+
+impl std::fmt::Display for Identity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// The trailer line signing off as `identity`
+pub fn trailer(identity: &Identity) -> String {
+    format!("{SIGNOFF_TOKEN}: {identity}")
+}
+
+/// Check the sign-offs of `message`; with `author`, one of them must be the author's
+pub fn check(message: &Message, author: Option<&Identity>) -> Result<(), ValidationError> {
+    let mut signers = Vec::new();
+    for footer in message.footers_named(SIGNOFF_TOKEN) {
+        let identity = Identity::parse(&footer.value)
+            .ok_or_else(|| ValidationError::InvalidSignoff(footer.value.clone()))?;
+        signers.push(identity);
+    }
+    if signers.is_empty() {
+        return Err(ValidationError::MissingSignoff);
+    }
+    if let Some(author) = author {
+        if !signers.iter().any(|signer| signer.same_as(author)) {
+            return Err(ValidationError::SignoffMismatch(author.to_string()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message;
+
+    fn ident(s: &str) -> Identity {
+        Identity::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parses_identities() {
+        assert_eq!(
+            Identity::parse("Jane Doe <jane@example.com> 1700000000 +0100"),
+            Some(Identity {
+                name: "Jane Doe".to_string(),
+                email: "jane@example.com".to_string(),
+            })
+        );
+        assert_eq!(Identity::parse("Jane Doe"), None);
+        assert_eq!(Identity::parse("<jane@example.com>"), None);
+        assert_eq!(
+            trailer(&ident("Jane <jane@example.com>")),
+            "Signed-off-by: Jane <jane@example.com>"
+        );
+    }
+
+    #[test]
+    fn requires_a_valid_signoff() {
+        let check_text = |text: &str| check(&message::parse(text), None);
+        assert!(matches!(
+            check_text("feat: x\n\nRefs: #1\n"),
+            Err(ValidationError::MissingSignoff)
+        ));
+        // A sign-off outside the trailer block doesn't count
+        assert!(matches!(
+            check_text("feat: x\n\nSigned-off-by: Jane <jane@example.com>\nmore body\n"),
+            Err(ValidationError::MissingSignoff)
+        ));
+        assert!(matches!(
+            check_text("feat: x\n\nSigned-off-by: Jane\n"),
+            Err(ValidationError::InvalidSignoff(value)) if value == "Jane"
+        ));
+        assert!(check_text("feat: x\n\nSigned-off-by: Jane <jane@example.com>\n").is_ok());
+    }
+
+    #[test]
+    fn matches_the_author_by_email() {
+        let parsed = message::parse(
+            "feat: x\n\nSigned-off-by: Jane <Jane@Example.com>\nSigned-off-by: Bot <bot@example.com>\n",
+        );
+        assert!(check(&parsed, Some(&ident("J. Doe <jane@example.com>"))).is_ok());
+        assert!(matches!(
+            check(&parsed, Some(&ident("Joe <joe@example.com>"))),
+            Err(ValidationError::SignoffMismatch(author)) if author == "Joe <joe@example.com>"
+        ));
+    }
+}
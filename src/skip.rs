@@ -0,0 +1,132 @@
+use crate::message::{self, append_trailer};
+
+/// Environment variable that lets a single commit bypass validation
+pub const SKIP_ENV_VAR: &str = "CC_CHECK_SKIP";
+
+/// Marker in the commit message that bypasses validation, unless configured otherwise
+pub const DEFAULT_SKIP_MARKER: &str = "[skip cc-check]";
+
+/// Trailer recording that a commit bypassed validation, and why
+pub const SKIP_TRAILER: &str = "Cc-Check-Skipped";
+
+/// Why validation of `message` should be skipped, if it should: `CC_CHECK_SKIP` set to
+/// anything but empty, `0` or `false`, or the (non-empty) `marker` in the message
+pub fn skip_reason(message: &str, marker: &str, env_value: Option<&str>) -> Option<String> {
+    let env_requested = env_value
+        .map(str::trim)
+        .is_some_and(|value| !matches!(value, "" | "0" | "false"));
+    if env_requested {
+        return Some(SKIP_ENV_VAR.to_string());
+    }
+    if !marker.is_empty() && message.contains(marker) {
+        return Some(marker.to_string());
+    }
+    None
+}
+
+/// The reason recorded in the `Cc-Check-Skipped` trailer of an existing commit; the
+/// line only counts in the footer block, not when quoted in the body, and only with a
+/// reason the hook could have recorded (`CC_CHECK_SKIP` or the non-empty `marker`)
+pub fn recorded_skip(message: &str, marker: &str) -> Option<String> {
+    message::parse(message)
+        .footers_named(SKIP_TRAILER)
+        .map(|footer| footer.value.as_str())
+        .find(|reason| *reason == SKIP_ENV_VAR || (!marker.is_empty() && *reason == marker))
+        .map(String::from)
+}
+
+/// Append a `Cc-Check-Skipped: <reason>` trailer to `message`, unless it has one;
+/// `comment` is the comment prefix, so the trailer lands above git's comments
+pub fn record_skip(message: &str, reason: &str, comment: &str) -> String {
+    // The raw message may still carry git's comments, so look for the line itself
+    let prefix = format!("{SKIP_TRAILER}:");
+    if message.lines().any(|line| line.starts_with(&prefix)) {
+        return message.to_string();
+    }
+    append_trailer(message, &format!("{SKIP_TRAILER}: {reason}"), comment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_var_and_marker_request_a_skip() {
+        assert_eq!(
+            skip_reason("bad header", DEFAULT_SKIP_MARKER, Some("1")).as_deref(),
+            Some("CC_CHECK_SKIP")
+        );
+        assert_eq!(
+            skip_reason("bad header", DEFAULT_SKIP_MARKER, Some("0")),
+            None
+        );
+        assert_eq!(
+            skip_reason("hotfix [skip cc-check]", DEFAULT_SKIP_MARKER, None).as_deref(),
+            Some("[skip cc-check]")
+        );
+        assert_eq!(skip_reason("hotfix [skip cc-check]", "", None), None);
+    }
+
+    #[test]
+    fn records_trailer_before_comments() {
+        let message = "hotfix: stop the bleeding\n\n# Please enter the commit message\n";
+        assert_eq!(
+            record_skip(message, "CC_CHECK_SKIP", "#"),
+            "hotfix: stop the bleeding\n\nCc-Check-Skipped: CC_CHECK_SKIP\n\n# Please enter the commit message\n"
+        );
+    }
+
+    #[test]
+    fn joins_existing_trailer_block() {
+        let message = "hotfix\n\nbody\n\nSigned-off-by: A <a@example.com>\n";
+        let recorded = record_skip(message, "CC_CHECK_SKIP", "#");
+        assert!(recorded
+            .ends_with("Signed-off-by: A <a@example.com>\nCc-Check-Skipped: CC_CHECK_SKIP\n"));
+        assert_eq!(
+            recorded_skip(&recorded, DEFAULT_SKIP_MARKER).as_deref(),
+            Some("CC_CHECK_SKIP")
+        );
+        assert_eq!(record_skip(&recorded, "again", "#"), recorded);
+    }
+
+    #[test]
+    fn only_a_trailer_records_a_skip() {
+        assert_eq!(
+            recorded_skip(
+                "fix: x\n\nCc-Check-Skipped: CC_CHECK_SKIP\nmore body\n",
+                DEFAULT_SKIP_MARKER
+            ),
+            None
+        );
+        assert_eq!(
+            recorded_skip(
+                "fix: x\n\nCc-Check-Skipped: CC_CHECK_SKIP\n\nbody\n",
+                DEFAULT_SKIP_MARKER
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn recorded_skip_needs_a_known_reason() {
+        let message = "fix: x\n\nCc-Check-Skipped: trust me\n";
+        assert_eq!(recorded_skip(message, DEFAULT_SKIP_MARKER), None);
+        let message = "fix: x\n\nCc-Check-Skipped: [skip cc-check]\n";
+        assert_eq!(
+            recorded_skip(message, DEFAULT_SKIP_MARKER).as_deref(),
+            Some("[skip cc-check]")
+        );
+        assert_eq!(recorded_skip(message, "[hotfix]"), None);
+        assert_eq!(recorded_skip(message, ""), None);
+    }
+
+    #[test]
+    fn records_trailer_after_body_ending_in_footer_like_line() {
+        let message = "hotfix\n\nSee the docs\nNote: this is tricky\n";
+        let recorded = record_skip(message, "CC_CHECK_SKIP", "#");
+        assert_eq!(
+            recorded_skip(&recorded, DEFAULT_SKIP_MARKER).as_deref(),
+            Some("CC_CHECK_SKIP")
+        );
+    }
+}
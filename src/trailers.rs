@@ -0,0 +1,367 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::message::{Footer, Message};
+use crate::signoff::Identity;
+use crate::ValidationError;
+
+/// Trailer carrying a co-author, as GitHub and GitLab understand it
+pub const CO_AUTHOR_TOKEN: &str = "Co-authored-by";
+
+/// How repeated trailers are treated
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DuplicatePolicy {
+    /// Any trailer may repeat
+    #[default]
+    Allow,
+    /// The same token may repeat, but not with the same value
+    ForbidIdentical,
+    /// Each token may appear once
+    Forbid,
+}
+
+/// Trailer rules, configured under `[trailers]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TrailerConfig {
+    /// Trailer tokens that may be used; empty allows any (`BREAKING CHANGE` always is)
+    pub allowed: Vec<String>,
+
+    /// Trailer tokens every commit must have
+    pub required: Vec<String>,
+
+    /// Relative order of the listed tokens; unlisted tokens may go anywhere
+    pub order: Vec<String>,
+
+    /// "allow", "forbid-identical" or "forbid"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<DuplicatePolicy>,
+
+    /// Value format per token: "identity" (`Name <email>`), "email", "commit" or a regex
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub formats: BTreeMap<String, String>,
+
+    /// Require `Co-authored-by` emails to appear in the repository's `.mailmap`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mailmap: Option<bool>,
+}
+
+impl TrailerConfig {
+    /// The built-in defaults, with every option set
+    pub fn defaults() -> Self {
+        Self {
+            allowed: Vec::new(),
+            required: Vec::new(),
+            order: Vec::new(),
+            duplicates: Some(DuplicatePolicy::Allow),
+            formats: [(CO_AUTHOR_TOKEN, "identity")]
+                .into_iter()
+                .map(|(token, format)| (token.to_string(), format.to_string()))
+                .collect(),
+            mailmap: Some(false),
+        }
+    }
+
+    /// Compile the configured formats
+    pub fn rules(&self) -> Result<TrailerRules> {
+        let formats = self
+            .formats
+            .iter()
+            .map(|(token, format)| {
+                let format = match format.as_str() {
+                    "identity" => TrailerFormat::Identity,
+                    "email" => TrailerFormat::Email,
+                    "commit" => TrailerFormat::Commit,
+                    pattern => TrailerFormat::Pattern(Regex::new(pattern).with_context(|| {
+                        format!("invalid trailers.formats.{token} pattern `{pattern}`")
+                    })?),
+                };
+                Ok((token.clone(), format))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(TrailerRules {
+            allowed: self.allowed.clone(),
+            required: self.required.clone(),
+            order: self.order.clone(),
+            duplicates: self.duplicates.unwrap_or_default(),
+            formats,
+        })
+    }
+}
+
+/// Expected shape of a trailer value
+#[derive(Debug, Clone)]
+pub enum TrailerFormat {
+    /// `Name <email>`
+    Identity,
+    /// A bare email address
+    Email,
+    /// A commit hash
+    Commit,
+    /// Any custom regex
+    Pattern(Regex),
+}
+
+impl TrailerFormat {
+    fn accepts(&self, value: &str) -> bool {
+        match self {
+            TrailerFormat::Identity => Identity::parse(value).is_some_and(|identity| {
+                is_valid_email(&identity.email) && value.trim_end().ends_with('>')
+            }),
+            TrailerFormat::Email => is_valid_email(value),
+            TrailerFormat::Commit => crate::is_commit_hash(value),
+            TrailerFormat::Pattern(pattern) => pattern.is_match(value),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            TrailerFormat::Identity => "`Name <email>`".to_string(),
+            TrailerFormat::Email => "an email address".to_string(),
+            TrailerFormat::Commit => "a commit hash".to_string(),
+            TrailerFormat::Pattern(pattern) => format!("to match `{pattern}`"),
+        }
+    }
+}
+
+/// Compiled trailer rules
+#[derive(Debug, Clone, Default)]
+pub struct TrailerRules {
+    pub allowed: Vec<String>,
+    pub required: Vec<String>,
+    pub order: Vec<String>,
+    pub duplicates: DuplicatePolicy,
+    pub formats: Vec<(String, TrailerFormat)>,
+}
+
+impl TrailerRules {
+    /// Check the footers of `message`; `mailmap`, if given, must know every co-author
+    pub fn check(
+        &self,
+        message: &Message,
+        mailmap: Option<&Mailmap>,
+    ) -> Result<(), ValidationError> {
+        let is = |footer: &Footer, token: &str| footer.token.eq_ignore_ascii_case(token);
+
+        for (index, footer) in message.footers.iter().enumerate() {
+            let breaking = footer.token.starts_with("BREAKING");
+            if !breaking && !self.allowed.is_empty() && !self.allowed.iter().any(|t| is(footer, t))
+            {
+                return Err(ValidationError::DisallowedTrailer(footer.token.clone()));
+            }
+
+            if let Some((_, format)) = self.formats.iter().find(|(token, _)| is(footer, token)) {
+                if !format.accepts(&footer.value) {
+                    return Err(ValidationError::InvalidTrailer {
+                        token: footer.token.clone(),
+                        value: footer.value.clone(),
+                        expected: format.describe(),
+                    });
+                }
+            }
+
+            if let (Some(mailmap), true) = (mailmap, is(footer, CO_AUTHOR_TOKEN)) {
+                let known = Identity::parse(&footer.value)
+                    .is_some_and(|identity| mailmap.knows(&identity.email));
+                if !known {
+                    return Err(ValidationError::UnknownCoAuthor(footer.value.clone()));
+                }
+            }
+
+            let earlier = &message.footers[..index];
+            let repeated = match self.duplicates {
+                DuplicatePolicy::Allow => false,
+                DuplicatePolicy::ForbidIdentical => earlier
+                    .iter()
+                    .any(|other| is(other, &footer.token) && other.value == footer.value),
+                DuplicatePolicy::Forbid => earlier.iter().any(|other| is(other, &footer.token)),
+            };
+            if repeated {
+                return Err(ValidationError::DuplicateTrailer(footer.token.clone()));
+            }
+
+            let rank = |footer: &Footer| self.order.iter().position(|t| is(footer, t));
+            if let Some(position) = rank(footer) {
+                if let Some(later) = earlier.iter().find(|other| rank(other) > Some(position)) {
+                    return Err(ValidationError::TrailerOrder(
+                        footer.token.clone(),
+                        later.token.clone(),
+                    ));
+                }
+            }
+        }
+
+        for token in &self.required {
+            if message.footers_named(token).next().is_none() {
+                return Err(ValidationError::MissingTrailer(token.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Whether `email` looks like `local@domain.tld`
+pub fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        return false;
+    };
+    let valid_local = !local.is_empty()
+        && !local
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '<' | '>' | '@' | ','));
+    let labels: Vec<&str> = domain.split('.').collect();
+    let valid_domain = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_alphanumeric() || c == '-')
+        });
+    valid_local && valid_domain
+}
+
+/// Email addresses listed in a `.mailmap`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mailmap {
+    emails: Vec<String>,
+}
+
+impl Mailmap {
+    /// Parse `.mailmap` contents; both proper and commit emails count as known
+    pub fn parse(contents: &str) -> Self {
+        let mut emails = Vec::new();
+        for line in contents.lines() {
+            let line = line.split_once('#').map_or(line, |(entry, _)| entry);
+            let mut rest = line;
+            while let Some((_, after)) = rest.split_once('<') {
+                let Some((email, after)) = after.split_once('>') else {
+                    break;
+                };
+                emails.push(email.trim().to_lowercase());
+                rest = after;
+            }
+        }
+        Self { emails }
+    }
+
+    /// Read the mailmap at `path`
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read mailmap: {}", path.display()))?;
+        Ok(Self::parse(&contents))
+    }
+
+    /// Whether `email` appears in the mailmap, ignoring case
+    pub fn knows(&self, email: &str) -> bool {
+        self.emails.contains(&email.to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::message;
+
+    fn check(config: &TrailerConfig, text: &str) -> Result<(), ValidationError> {
+        config.rules().unwrap().check(&message::parse(text), None)
+    }
+
+    #[test]
+    fn validates_email_syntax() {
+        assert!(is_valid_email("jane@example.com"));
+        assert!(is_valid_email("123+jane@users.noreply.github.com"));
+        assert!(!is_valid_email("jane@localhost"));
+        assert!(!is_valid_email("jane example@example.com"));
+        assert!(!is_valid_email("@example.com"));
+        assert!(!is_valid_email("jane@-example.com"));
+    }
+
+    #[test]
+    fn co_authors_need_name_and_email_by_default() {
+        let config = TrailerConfig::defaults();
+        assert!(check(
+            &config,
+            "feat: x\n\nCo-authored-by: Jane <jane@example.com>\n"
+        )
+        .is_ok());
+        assert!(matches!(
+            check(&config, "feat: x\n\nCo-authored-by: jane@example\n"),
+            Err(ValidationError::InvalidTrailer { token, .. }) if token == "Co-authored-by"
+        ));
+    }
+
+    #[test]
+    fn allowed_required_and_custom_formats() {
+        let config = TrailerConfig {
+            allowed: vec!["Reviewed-by".to_string(), "Refs".to_string()],
+            required: vec!["Reviewed-by".to_string()],
+            formats: [("Refs".to_string(), r"^#\d+$".to_string())].into(),
+            ..TrailerConfig::defaults()
+        };
+        assert!(check(&config, "feat: x\n\nReviewed-by: A\nBREAKING CHANGE: y\n").is_ok());
+        assert!(matches!(
+            check(&config, "feat: x\n\nAcked-by: A\n"),
+            Err(ValidationError::DisallowedTrailer(token)) if token == "Acked-by"
+        ));
+        assert!(matches!(
+            check(&config, "feat: x\n\nRefs: #1\n"),
+            Err(ValidationError::MissingTrailer(token)) if token == "Reviewed-by"
+        ));
+        assert!(matches!(
+            check(&config, "feat: x\n\nReviewed-by: A\nRefs: PROJ-1\n"),
+            Err(ValidationError::InvalidTrailer { .. })
+        ));
+    }
+
+    #[test]
+    fn order_and_duplicates() {
+        let config = TrailerConfig {
+            order: vec!["Refs".to_string(), "Signed-off-by".to_string()],
+            duplicates: Some(DuplicatePolicy::ForbidIdentical),
+            ..TrailerConfig::defaults()
+        };
+        assert!(check(
+            &config,
+            "feat: x\n\nRefs: #1\nAcked-by: A\nSigned-off-by: A <a@b.io>\n"
+        )
+        .is_ok());
+        assert!(matches!(
+            check(&config, "feat: x\n\nSigned-off-by: A <a@b.io>\nRefs: #1\n"),
+            Err(ValidationError::TrailerOrder(first, second))
+                if first == "Refs" && second == "Signed-off-by"
+        ));
+        assert!(check(&config, "feat: x\n\nRefs: #1\nRefs: #2\n").is_ok());
+        assert!(matches!(
+            check(&config, "feat: x\n\nRefs: #1\nRefs: #1\n"),
+            Err(ValidationError::DuplicateTrailer(token)) if token == "Refs"
+        ));
+
+        let config = TrailerConfig {
+            duplicates: Some(DuplicatePolicy::Forbid),
+            ..TrailerConfig::defaults()
+        };
+        assert!(check(&config, "feat: x\n\nRefs: #1\nRefs: #2\n").is_err());
+    }
+
+    #[test]
+    fn co_authors_checked_against_mailmap() {
+        let mailmap = Mailmap::parse(
+            "# contributors\nJane Doe <jane@example.com> <jdoe@old.example.com>\n<ops@example.com> # team\n",
+        );
+        assert!(mailmap.knows("JDoe@old.example.com"));
+        assert!(mailmap.knows("ops@example.com"));
+
+        let rules = TrailerConfig::defaults().rules().unwrap();
+        let check = |text: &str| rules.check(&message::parse(text), Some(&mailmap));
+        assert!(check("feat: x\n\nCo-authored-by: Jane <jane@example.com>\n").is_ok());
+        assert!(matches!(
+            check("feat: x\n\nCo-authored-by: Eve <eve@example.com>\n"),
+            Err(ValidationError::UnknownCoAuthor(value)) if value == "Eve <eve@example.com>"
+        ));
+    }
+}
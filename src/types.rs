@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+
+/// Version bump a commit type implies when releasing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverImpact {
+    Major,
+    Minor,
+    Patch,
+    None,
+}
+
+impl SemverImpact {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SemverImpact::Major => "major",
+            SemverImpact::Minor => "minor",
+            SemverImpact::Patch => "patch",
+            SemverImpact::None => "none",
+        }
+    }
+}
+
+/// Metadata for a commit type, configured under `[type_info.<type>]`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TypeInfo {
+    /// Short description, e.g. for an interactive commit wizard
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    /// Changelog section commits of this type are listed under; unset or `""` to omit
+    /// them (`""` also removes a built-in type's section)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub changelog_section: Option<String>,
+
+    /// Version bump implied by commits of this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub semver: Option<SemverImpact>,
+}
+
+impl TypeInfo {
+    /// Fill unset fields from `fallback`; an empty `changelog_section` stays omitted
+    pub fn or(self, fallback: TypeInfo) -> TypeInfo {
+        TypeInfo {
+            description: self.description.or(fallback.description),
+            changelog_section: self
+                .changelog_section
+                .or(fallback.changelog_section)
+                .filter(|section| !section.is_empty()),
+            semver: self.semver.or(fallback.semver),
+        }
+    }
+}
+
+/// An allowed commit type together with its metadata
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TypeDefinition {
+    pub name: String,
+    #[serde(flatten)]
+    pub info: TypeInfo,
+}
+
+/// Built-in metadata for the default (Angular) types
+pub fn builtin_info(name: &str) -> TypeInfo {
+    let (description, section, semver) = match name {
+        "feat" => ("A new feature", Some("Features"), SemverImpact::Minor),
+        "fix" => ("A bug fix", Some("Bug Fixes"), SemverImpact::Patch),
+        "docs" => (
+            "Documentation only changes",
+            Some("Documentation"),
+            SemverImpact::None,
+        ),
+        "style" => (
+            "Changes that do not affect the meaning of the code",
+            None,
+            SemverImpact::None,
+        ),
+        "refactor" => (
+            "A code change that neither fixes a bug nor adds a feature",
+            Some("Code Refactoring"),
+            SemverImpact::None,
+        ),
+        "perf" => (
+            "A code change that improves performance",
+            Some("Performance Improvements"),
+            SemverImpact::Patch,
+        ),
+        "test" => (
+            "Adding missing tests or correcting existing tests",
+            None,
+            SemverImpact::None,
+        ),
+        "build" => (
+            "Changes that affect the build system or external dependencies",
+            Some("Build System"),
+            SemverImpact::None,
+        ),
+        "ci" => (
+            "Changes to CI configuration files and scripts",
+            Some("Continuous Integration"),
+            SemverImpact::None,
+        ),
+        "chore" => (
+            "Other changes that don't modify src or test files",
+            None,
+            SemverImpact::None,
+        ),
+        "revert" => (
+            "Reverts a previous commit",
+            Some("Reverts"),
+            SemverImpact::Patch,
+        ),
+        _ => return TypeInfo::default(),
+    };
+
+    TypeInfo {
+        description: Some(description.to_string()),
+        changelog_section: section.map(String::from),
+        semver: Some(semver),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_info_covers_default_types() {
+        for name in crate::DEFAULT_TYPES {
+            assert!(builtin_info(name).description.is_some(), "{name}");
+        }
+        assert_eq!(builtin_info("wip"), TypeInfo::default());
+    }
+
+    #[test]
+    fn configured_info_overrides_builtin_fields() {
+        let configured = TypeInfo {
+            changelog_section: Some("New Stuff".to_string()),
+            ..TypeInfo::default()
+        };
+        let info = configured.or(builtin_info("feat"));
+        assert_eq!(info.changelog_section.as_deref(), Some("New Stuff"));
+        assert_eq!(info.semver, Some(SemverImpact::Minor));
+        assert_eq!(info.description.as_deref(), Some("A new feature"));
+    }
+
+    #[test]
+    fn empty_changelog_section_omits_builtin_section() {
+        let configured = TypeInfo {
+            changelog_section: Some(String::new()),
+            ..TypeInfo::default()
+        };
+        let info = configured.or(builtin_info("docs"));
+        assert_eq!(info.changelog_section, None);
+        assert_eq!(info.semver, Some(SemverImpact::None));
+    }
+}
@@ -0,0 +1,84 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::{git, init_repo};
+
+/// Create a git repository on `branch` with the given branch-rule mode
+fn create_repo(branch: &str, mode: &str) -> TempDir {
+    let temp_dir = init_repo();
+    git(temp_dir.path(), &["checkout", "-q", "-b", branch]);
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        format!("[branch]\nmode = \"{mode}\"\n"),
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+#[test]
+fn enforce_mode_requires_matching_type_and_ticket() {
+    let repo = create_repo("feature/PROJ-123-login", "enforce");
+
+    check(repo.path(), "feat: add login\n\nRefs: PROJ-123\n").success();
+    check(repo.path(), "fix: add login PROJ-123")
+        .failure()
+        .stderr(predicate::str::contains(
+            "commit type 'fix' does not match branch 'feature/PROJ-123-login' (expected 'feat')",
+        ));
+    check(repo.path(), "feat: add login")
+        .failure()
+        .stderr(predicate::str::contains(
+            "commit must reference PROJ-123 from branch 'feature/PROJ-123-login'",
+        ));
+}
+
+#[test]
+fn ticket_must_match_as_a_whole_word() {
+    let repo = create_repo("feat/PROJ-12-x", "enforce");
+
+    check(repo.path(), "feat: add x\n\nRefs: PROJ-12\n").success();
+    for message in [
+        "feat: add x\n\nRefs: PROJ-123\n",
+        "feat: add x\n\nSee https://jira.example.com/browse/PROJ-12\n",
+        "feat: add x for XPROJ-12",
+    ] {
+        check(repo.path(), message)
+            .failure()
+            .stderr(predicate::str::contains(
+                "commit must reference PROJ-12 from branch 'feat/PROJ-12-x'",
+            ));
+    }
+}
+
+#[test]
+fn suggest_mode_only_hints() {
+    let repo = create_repo("feat/PROJ-7-search", "suggest");
+
+    check(repo.path(), "feat: add search")
+        .success()
+        .stderr(predicate::str::contains(
+            "hint: add `Refs: PROJ-7` to reference the ticket from branch 'feat/PROJ-7-search'",
+        ));
+}
+
+#[test]
+fn branches_not_matching_the_pattern_are_ignored() {
+    let repo = create_repo("main", "enforce");
+    check(repo.path(), "docs: update readme").success();
+}
@@ -98,3 +98,69 @@ fn cli_handles_comments_followed_by_valid_commit() {
         .assert()
         .success();
 }
+
+#[test]
+fn cli_drops_verbose_diff_below_scissors_line() {
+    let file = write_temp(
+        "feat: add feature\n\
+         # ------------------------ >8 ------------------------\n\
+         # Do not modify or remove the line above.\n\
+         diff --git a/x b/x\n",
+    );
+    Command::new(cargo_bin!("cc-check"))
+        .args([
+            "check",
+            "--cleanup",
+            "scissors",
+            "--ignore-comments",
+            "false",
+        ])
+        .arg(file.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn cli_uses_configured_comment_char() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let git = |args: &[&str]| {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(temp_dir.path())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    };
+    git(&["init", "-q"]);
+    git(&["config", "core.commentChar", ";"]);
+
+    let msg_file = temp_dir.path().join("COMMIT_EDITMSG");
+    std::fs::write(
+        &msg_file,
+        "; Please enter the commit message\nfeat: add x\n",
+    )
+    .unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    std::fs::write(&msg_file, "#123 is fixed\n").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure();
+
+    git(&["config", "commit.cleanup", "verbatim"]);
+    std::fs::write(&msg_file, "; not a comment\nfeat: add x\n").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure();
+}
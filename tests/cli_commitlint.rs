@@ -0,0 +1,138 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+const COMMITLINT_JSON: &str = r#"{
+  "extends": ["@commitlint/config-conventional"],
+  "rules": {
+    "scope-enum": [2, "always", ["api", "web"]],
+    "body-max-line-length": [2, "always", 20]
+  }
+}"#;
+
+/// Create a directory holding only the given commitlint config
+fn create_repo(name: &str, config: &str) -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join(name), config).unwrap();
+    std::fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[package]\nname = \"test\"",
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join("commit_msg.txt");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .arg("check")
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+fn migrate(repo: &Path) -> Command {
+    let mut cmd = Command::new(cargo_bin!("cc-check"));
+    cmd.args(["migrate", "--from", "commitlint"])
+        .current_dir(repo);
+    cmd
+}
+
+#[test]
+fn commitlint_json_config_is_used() {
+    let repo = create_repo(".commitlintrc.json", COMMITLINT_JSON);
+
+    check(repo.path(), "feat(api): add endpoint\n")
+        .success()
+        .stderr(
+            predicate::str::is_match(
+                r"note: .*\.commitlintrc\.json: rule `footer-max-line-length` is not supported",
+            )
+            .unwrap(),
+        );
+    check(repo.path(), "feat(db): add index\n")
+        .failure()
+        .stderr(predicate::str::contains(
+            "scope must be one of api, web [scope-enum]",
+        ));
+    check(repo.path(), "feat(api): Add endpoint\n")
+        .failure()
+        .stderr(predicate::str::contains(
+            "subject must not be sentence-case or upper-case [subject-case]",
+        ));
+    check(repo.path(), "feat(api): API docs for the endpoint\n").success();
+    check(repo.path(), "wip: add endpoint\n").failure();
+    check(
+        repo.path(),
+        "fix(web): wrap text\n\nthis line is longer than twenty characters\n",
+    )
+    .failure()
+    .stderr(predicate::str::contains(
+        "body lines must not be longer than 20 characters",
+    ));
+}
+
+#[test]
+fn commitlint_yaml_config_is_used() {
+    let repo = create_repo(
+        ".commitlintrc.yml",
+        "rules:\n  type-enum: [2, always, [feat, fix]]\n  subject-full-stop: [2, never, '.']\n",
+    );
+
+    check(repo.path(), "fix: handle empty input\n").success();
+    check(repo.path(), "docs: describe input\n").failure();
+    check(repo.path(), "fix: handle empty input.\n").failure();
+}
+
+#[test]
+fn cc_check_config_wins_over_commitlint() {
+    let repo = create_repo(".commitlintrc.json", COMMITLINT_JSON);
+    std::fs::write(repo.path().join(".cc-check.toml"), "types = [\"wip\"]\n").unwrap();
+
+    check(repo.path(), "wip: add endpoint\n").success();
+}
+
+#[test]
+fn migrate_writes_equivalent_config() {
+    let repo = create_repo(".commitlintrc.json", COMMITLINT_JSON);
+
+    migrate(repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("wrote .cc-check.toml"))
+        .stderr(predicate::str::contains(
+            "note: rule `footer-max-line-length` is not supported and was ignored",
+        ));
+    let written = std::fs::read_to_string(repo.path().join(".cc-check.toml")).unwrap();
+    assert!(written.starts_with("# Migrated from .commitlintrc.json"));
+    assert!(written.contains("id = \"scope-enum\""));
+
+    std::fs::remove_file(repo.path().join(".commitlintrc.json")).unwrap();
+    check(repo.path(), "feat(api): add endpoint\n").success();
+    check(repo.path(), "feat(db): add index\n").failure();
+
+    migrate(repo.path())
+        .arg(repo.path().join("missing.json"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            ".cc-check.toml already exists; pass --force to overwrite it",
+        ));
+}
+
+#[test]
+fn migrate_refuses_javascript_configs() {
+    let repo = create_repo("commitlint.config.js", "module.exports = {};\n");
+
+    migrate(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "npx commitlint --print-config json",
+        ));
+}
@@ -1,11 +1,12 @@
 use assert_cmd::prelude::*;
 #[macro_use]
 extern crate assert_cmd;
+use predicates::prelude::*;
 use std::process::Command;
 use tempfile::TempDir;
 
 mod common;
-use common::write_temp;
+use common::{commit, git, init_repo, write_temp};
 
 /// Helper to create a temp directory with a config file and Cargo.toml (for repo detection)
 fn create_temp_repo_with_config(config_content: &str) -> TempDir {
@@ -39,6 +40,204 @@ fn config_file_allows_extra_types() {
         .success();
 }
 
+#[test]
+fn legacy_invocation_honours_config_file_and_flags() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        extra_types = ["wip"]
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "wip: work in progress").unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::is_empty());
+
+    Command::new(cargo_bin!("cc-check"))
+        .arg(&msg_file)
+        .args(["--max-subject", "5", "--format", "json"])
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"ok\":false"));
+}
+
+#[test]
+fn legacy_invocation_warns_when_deprecations_enabled() {
+    let temp_repo = create_temp_repo_with_config("");
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat: add feature").unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .arg(&msg_file)
+        .env("CC_CHECK_DEPRECATIONS", "1")
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "use `cc-check check <COMMIT_MSG_FILE>`",
+        ));
+}
+
+#[test]
+fn empty_git_messages_rejects_merge_headers() {
+    let temp_repo = create_temp_repo_with_config("git_messages = []\n");
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "Merge branch 'feature'\n").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("header must match"));
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show"])
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"git_messages = \[\]  # .*\.cc-check\.toml").unwrap());
+}
+
+#[test]
+fn empty_reference_locations_turn_extraction_off() {
+    let temp_repo = create_temp_repo_with_config("[references]\nlocations = []\n");
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "fix: handle PROJ-12\n\nRefs: #34\n").unwrap();
+    let output = Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--format", "json"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], true);
+    assert!(json
+        .get("references")
+        .is_none_or(|refs| refs == &serde_json::json!([])));
+}
+
+#[test]
+fn config_git_messages_can_require_conventional_revert() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        git_messages = ["merge-branch", "merge-pull-request"]
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(
+        &msg_file,
+        "Revert \"feat: add x\"\n\nThis reverts commit 1a2b3c4d5e6f.\n",
+    )
+    .unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "use a conventional `revert: <header>` commit",
+        ));
+
+    std::fs::write(&msg_file, "Merge tag 'v1.0.0'").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure();
+
+    std::fs::write(&msg_file, "Merge branch 'feature/x' into main").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_requires_revert_refs() {
+    let temp_repo = create_temp_repo_with_config("require_revert_refs = true\n");
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "revert: add feature").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "revert commits must reference the reverted commit",
+        ));
+
+    std::fs::write(&msg_file, "revert: add feature\n\nRefs: 1a2b3c4\n").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_references_required_for_types() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        [references]
+        required_for = ["feat", "fix"]
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat: add login").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "feat commits must reference an issue",
+        ));
+
+    std::fs::write(&msg_file, "docs: update readme").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+
+    std::fs::write(&msg_file, "feat: add login PROJ-12\n\nCloses #34\n").unwrap();
+    let output = Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--format", "json"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json["references"],
+        serde_json::json!([
+            { "value": "PROJ-12", "location": "subject" },
+            { "value": "#34", "location": "footer" },
+        ])
+    );
+}
+
 #[test]
 fn config_file_sets_max_subject() {
     let temp_repo = create_temp_repo_with_config(
@@ -59,6 +258,44 @@ fn config_file_sets_max_subject() {
         .failure();
 }
 
+#[test]
+fn config_file_limits_header_in_display_columns() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        max_header = 16
+        length_unit = "columns"
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat(ui): 添加菜单").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("header exceeds 16 columns (18)"));
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--length-unit", "chars"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+
+    std::fs::write(&msg_file, "feat(ui): x").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--min-subject", "3"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "subject must be at least 3 columns (1)",
+        ));
+}
+
 #[test]
 fn config_file_allows_trailing_period() {
     let temp_repo = create_temp_repo_with_config(
@@ -171,3 +408,444 @@ fn config_file_with_multiple_options() {
         .assert()
         .success();
 }
+
+#[test]
+fn invalid_config_file_fails_check() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+max_subjet = 20
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat: add feature").unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(".cc-check.toml:2:1"))
+        .stderr(predicate::str::contains("did you mean `max_subject`?"));
+}
+
+#[test]
+fn config_validate_accepts_valid_file() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        max_subject = 50
+    "#,
+    );
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "validate"])
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("is valid"));
+}
+
+#[test]
+fn config_validate_rejects_invalid_file() {
+    let temp_repo = create_temp_repo_with_config("max_subject = \"long\"\n");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "validate"])
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(":1:15:"))
+        .stderr(predicate::str::contains("expected usize"));
+}
+
+#[test]
+fn config_validate_accepts_explicit_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_path = temp_dir.path().join("custom.toml");
+    std::fs::write(&config_path, "allow_merge_commits = \"no\"\n").unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "validate"])
+        .arg(&config_path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("custom.toml:1:23"));
+}
+
+#[test]
+fn config_show_reports_origin_of_each_value() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        max_subject = 50
+    "#,
+    );
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show", "--ignore-comments", "false"])
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("max_subject = 50  # "))
+        .stdout(predicate::str::contains(".cc-check.toml"))
+        .stdout(predicate::str::contains(
+            "ignore_comments = false  # CLI flag --ignore-comments",
+        ))
+        .stdout(predicate::str::contains(
+            "no_trailing_period = true  # default",
+        ));
+}
+
+#[test]
+fn env_vars_override_config_file_but_not_cli_flags() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        max_subject = 50
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(
+        &msg_file,
+        "wip: a subject longer than fifty characters in total length",
+    )
+    .unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .env("CC_CHECK_MAX_SUBJECT", "80")
+        .env("CC_CHECK_EXTRA_TYPES", "wip")
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--max-subject", "40"])
+        .arg(&msg_file)
+        .env("CC_CHECK_MAX_SUBJECT", "80")
+        .env("CC_CHECK_EXTRA_TYPES", "wip")
+        .env("CC_CHECK_FORMAT", "json")
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("\"ok\":false"));
+}
+
+#[cfg(unix)]
+#[test]
+fn non_utf8_env_vars_are_only_an_error_for_cc_check_keys() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let temp_repo = create_temp_repo_with_config("");
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat: add feature").unwrap();
+    let not_utf8 = OsStr::from_bytes(b"caf\xe9");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .env("UNRELATED_VAR", not_utf8)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .env("CC_CHECK_EXTRA_TYPES", not_utf8)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "invalid value in CC_CHECK_EXTRA_TYPES: not valid UTF-8",
+        ));
+}
+
+#[test]
+fn config_show_reports_env_origin() {
+    let temp_repo = create_temp_repo_with_config("");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show"])
+        .env("CC_CHECK_MAX_SUBJECT", "60")
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "max_subject = 60  # env CC_CHECK_MAX_SUBJECT",
+        ));
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show"])
+        .env("CC_CHECK_MAX_SUBJET", "60")
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "did you mean CC_CHECK_MAX_SUBJECT?",
+        ));
+}
+
+#[test]
+fn config_show_outputs_json() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        extra_types = ["wip"]
+    "#,
+    );
+
+    let output = Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show", "--format", "json"])
+        .current_dir(temp_repo.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["extra_types"]["value"], serde_json::json!(["wip"]));
+    assert_eq!(json["extra_types"]["origin"]["kind"], "file");
+    assert_eq!(json["max_subject"]["value"], 72);
+    assert_eq!(json["max_subject"]["origin"]["kind"], "default");
+}
+
+#[test]
+fn config_file_extends_shared_base() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        extends = ["shared/base.toml"]
+        extra_types_add = ["release"]
+    "#,
+    );
+    let shared = temp_repo.path().join("shared");
+    std::fs::create_dir(&shared).unwrap();
+    std::fs::write(
+        shared.join("base.toml"),
+        "extra_types = [\"wip\"]\nmax_subject = 20\n",
+    )
+    .unwrap();
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "wip: inherited type").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+
+    std::fs::write(&msg_file, "release: this subject is longer than twenty").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("subject exceeds 20 characters"));
+}
+
+#[test]
+fn config_show_reports_base_file_origin() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        extends = ["base.toml"]
+    "#,
+    );
+    std::fs::write(temp_repo.path().join("base.toml"), "max_subject = 20\n").unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show"])
+        .current_dir(temp_repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("max_subject = 20  # "))
+        .stdout(predicate::str::contains("base.toml"))
+        .stdout(predicate::str::contains("extends").not());
+}
+
+#[test]
+fn config_types_replace_default_list() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        types = ["feat", "fix"]
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "chore: bump deps").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("type 'chore' is not allowed"));
+}
+
+#[test]
+fn config_disallowed_types_remove_default_types() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        disallowed_types = ["style"]
+    "#,
+    );
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "style: reformat").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure();
+
+    std::fs::write(&msg_file, "fix: reformat").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_types_lists_metadata() {
+    let temp_repo = create_temp_repo_with_config(
+        r#"
+        types = ["feat", "deps"]
+
+        [type_info.deps]
+        description = "Dependency updates"
+        changelog_section = "Dependencies"
+        semver = "patch"
+    "#,
+    );
+
+    let output = Command::new(cargo_bin!("cc-check"))
+        .args(["config", "types", "--format", "json"])
+        .current_dir(temp_repo.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json[0]["name"], "feat");
+    assert_eq!(json[0]["semver"], "minor");
+    assert_eq!(json[1]["name"], "deps");
+    assert_eq!(json[1]["changelog_section"], "Dependencies");
+}
+
+#[test]
+fn config_flag_selects_explicit_file() {
+    let temp_repo = create_temp_repo_with_config("max_subject = 72\n");
+    let custom = temp_repo.path().join("strict.toml");
+    std::fs::write(&custom, "max_subject = 10\n").unwrap();
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat: a subject over ten").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--config"])
+        .arg(&custom)
+        .arg(&msg_file)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("subject exceeds 10 characters"));
+}
+
+#[test]
+fn config_env_var_selects_explicit_file() {
+    let temp_repo = create_temp_repo_with_config("max_subject = 72\n");
+    let custom = temp_repo.path().join("strict.toml");
+    std::fs::write(&custom, "max_subject = 10\n").unwrap();
+
+    let msg_file = temp_repo.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "feat: a subject over ten").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .env("CC_CHECK_CONFIG", &custom)
+        .current_dir(temp_repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("subject exceeds 10 characters"));
+}
+
+#[test]
+fn config_discovered_in_pyproject() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+    std::fs::write(
+        temp_dir.path().join("pyproject.toml"),
+        "[project]\nname = \"demo\"\n\n[tool.cc-check]\nextra_types = [\"wip\"]\n",
+    )
+    .unwrap();
+
+    let msg_file = temp_dir.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "wip: from pyproject").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_at_git_root_found_from_nested_crate() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        "extra_types = [\"wip\"]\n",
+    )
+    .unwrap();
+    let nested = temp_dir.path().join("tools").join("inner");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(nested.join("Cargo.toml"), "[package]\nname = \"inner\"").unwrap();
+
+    let msg_file = temp_dir.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "wip: nested crate").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(&nested)
+        .assert()
+        .success();
+}
+
+#[test]
+fn config_found_from_subdirectory_of_linked_worktree() {
+    let repo = init_repo();
+    std::fs::write(
+        repo.path().join(".cc-check.toml"),
+        "extra_types = [\"wip\"]\n",
+    )
+    .unwrap();
+    git(repo.path(), &["add", ".cc-check.toml"]);
+    commit(repo.path(), "chore: add config");
+    let outside = TempDir::new().unwrap();
+    let worktree = outside.path().join("wt");
+    git(
+        repo.path(),
+        &["worktree", "add", "-q", worktree.to_str().unwrap()],
+    );
+    let sub_dir = worktree.join("docs");
+    std::fs::create_dir(&sub_dir).unwrap();
+
+    let msg_file = outside.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "wip: from a worktree").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(&sub_dir)
+        .assert()
+        .success();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show"])
+        .current_dir(&sub_dir)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_match(r"# git root: .*[/\\]wt\n").unwrap());
+}
@@ -0,0 +1,114 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::{commit, init_repo_with_base};
+
+const RULES: &str = r#"
+[[custom_rules]]
+id = "fix-needs-ticket"
+target = "footer:Refs"
+pattern = '^PROJ-\d+$'
+when = { type = ["fix"] }
+message = "fix commits need a `Refs: PROJ-<n>` footer"
+
+[[custom_rules]]
+id = "no-please"
+target = "subject"
+forbid_pattern = '(?i)\bplease\b'
+severity = "warning"
+message = "subjects are not requests"
+"#;
+
+/// Create a git repository with the custom rules above and an initial commit
+fn create_repo() -> TempDir {
+    let temp_dir = init_repo_with_base();
+    std::fs::write(temp_dir.path().join(".cc-check.toml"), RULES).unwrap();
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str, format: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--format", format])
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+#[test]
+fn custom_rules_in_text_output() {
+    let repo = create_repo();
+
+    check(repo.path(), "feat: add login\n", "text").success();
+    check(repo.path(), "fix: login crash\n", "text")
+        .failure()
+        .stderr(predicate::str::contains(
+            "fix commits need a `Refs: PROJ-<n>` footer [fix-needs-ticket]",
+        ));
+    check(
+        repo.path(),
+        "fix: please fix login\n\nRefs: PROJ-1\n",
+        "text",
+    )
+    .success()
+    .stderr(predicate::str::contains(
+        "warning: subjects are not requests [no-please]",
+    ));
+}
+
+#[test]
+fn custom_rules_in_json_output() {
+    let repo = create_repo();
+
+    check(repo.path(), "fix: login crash\n", "json")
+        .failure()
+        .stdout(predicate::str::contains(
+            r#""violations":[{"rule":"fix-needs-ticket","message":"fix commits need a `Refs: PROJ-<n>` footer","severity":"error","target":"footer:Refs"}]"#,
+        ));
+    check(repo.path(), "feat: please add login\n", "json")
+        .success()
+        .stdout(predicate::str::contains(
+            r#""violations":[{"rule":"no-please","message":"subjects are not requests","severity":"warning","target":"subject"}]"#,
+        ));
+}
+
+#[test]
+fn custom_rules_in_range_output() {
+    let repo = create_repo();
+    commit(repo.path(), "feat: please add login");
+    commit(repo.path(), "fix: login crash");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--range", "base..HEAD"])
+        .current_dir(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "feat: please add login: subjects are not requests [no-please]",
+        ))
+        .stderr(predicate::str::contains(
+            "fix: login crash: fix commits need a `Refs: PROJ-<n>` footer [fix-needs-ticket]",
+        ));
+}
+
+#[test]
+fn invalid_custom_rule_is_a_config_error() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        "[[custom_rules]]\nid = \"x\"\ntarget = \"trailer\"\npattern = \".\"\n",
+    )
+    .unwrap();
+    std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+
+    check(temp_dir.path(), "feat: x\n", "text")
+        .failure()
+        .stderr(predicate::str::contains("unknown target `trailer`"));
+}
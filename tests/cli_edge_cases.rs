@@ -155,13 +155,34 @@ fn cli_rejects_type_with_hyphens() {
 
 #[test]
 fn cli_handles_revert_commit() {
-    let file = write_temp("Revert \"feat: add feature\"");
+    let file = write_temp(
+        "Revert \"feat: add feature\"\n\nThis reverts commit 1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b.",
+    );
     Command::new(cargo_bin!("cc-check"))
         .arg(file.path())
         .assert()
         .success();
 }
 
+#[test]
+fn cli_rejects_revert_without_reverted_commit() {
+    let file = write_temp("Revert \"feat: add feature\"");
+    Command::new(cargo_bin!("cc-check"))
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("header must match"));
+}
+
+#[test]
+fn cli_rejects_merge_lookalike() {
+    let file = write_temp("Merge stuff I did");
+    Command::new(cargo_bin!("cc-check"))
+        .arg(file.path())
+        .assert()
+        .failure();
+}
+
 #[test]
 fn cli_handles_merge_commit_with_branch() {
     let file = write_temp("Merge branch 'feature/new-feature'");
@@ -192,7 +213,9 @@ fn cli_rejects_merge_when_disabled() {
 
 #[test]
 fn cli_rejects_revert_when_merge_disabled() {
-    let file = write_temp("Revert \"feat: add feature\"");
+    let file = write_temp(
+        "Revert \"feat: add feature\"\n\nThis reverts commit 1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b.",
+    );
     Command::new(cargo_bin!("cc-check"))
         .args(["check", "--allow-merge-commits=false"])
         .arg(file.path())
@@ -233,3 +256,38 @@ fn cli_handles_unicode_subject_length() {
         .assert()
         .success();
 }
+
+#[test]
+fn cli_accepts_fixup_of_valid_header() {
+    for message in [
+        "fixup! feat: add feature",
+        "squash! fixup! fix(parser): handle tabs",
+        "amend! docs: update readme\n\ndocs: update the readme",
+        "fixup! 1a2b3c4d",
+    ] {
+        let file = write_temp(message);
+        Command::new(cargo_bin!("cc-check"))
+            .arg(file.path())
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn cli_validates_replacement_header_of_amend() {
+    let file = write_temp("amend! docs: update readme\n\nupdate the readme\n\nMore detail.");
+    Command::new(cargo_bin!("cc-check"))
+        .arg(file.path())
+        .assert()
+        .failure();
+}
+
+#[test]
+fn cli_validates_header_embedded_in_fixup() {
+    let file = write_temp("fixup! update stuff");
+    Command::new(cargo_bin!("cc-check"))
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("header must match"));
+}
@@ -5,7 +5,7 @@ use predicates::prelude::*;
 use std::process::Command;
 
 mod common;
-use common::write_temp;
+use common::{init_repo, write_temp};
 
 #[test]
 fn pre_commit_hook_accepts_valid_commit() {
@@ -45,3 +45,26 @@ fn install_command_shows_help() {
         .success()
         .stdout(predicate::str::contains("Install git commit-msg hook"));
 }
+
+#[cfg(unix)]
+#[test]
+fn pre_commit_hook_runs_binary_of_nested_crate() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = init_repo();
+    let crate_dir = repo.path().join("tools").join("cc-check");
+    let release = crate_dir.join("target").join("release");
+    std::fs::create_dir_all(&release).unwrap();
+    std::fs::create_dir_all(crate_dir.join("src")).unwrap();
+    std::fs::write(crate_dir.join("Cargo.toml"), "[package]\nname = \"inner\"").unwrap();
+    let binary = release.join("cc-check");
+    std::fs::write(&binary, "#!/bin/sh\necho \"nested binary: $*\"\n").unwrap();
+    std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    Command::new(cargo_bin!("pre-commit-hook"))
+        .arg("msg.txt")
+        .current_dir(crate_dir.join("src"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("nested binary: check msg.txt"));
+}
@@ -0,0 +1,97 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::{commit, git, init_repo};
+
+/// Create a git repository with a root config and a stricter config in packages/api
+fn create_monorepo() -> TempDir {
+    let temp_dir = init_repo();
+
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        "extra_types = [\"wip\"]\n",
+    )
+    .unwrap();
+    for package in ["api", "web"] {
+        std::fs::create_dir_all(temp_dir.path().join("packages").join(package)).unwrap();
+    }
+    std::fs::write(
+        temp_dir.path().join("packages/api/.cc-check.toml"),
+        "max_subject = 20\n",
+    )
+    .unwrap();
+
+    temp_dir
+}
+
+fn stage(repo: &Path, file: &str) {
+    std::fs::write(repo.join(file), "content").unwrap();
+    git(repo, &["add", file]);
+}
+
+fn check(repo: &Path, message: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+#[test]
+fn package_config_applies_to_commits_inside_package() {
+    let repo = create_monorepo();
+    stage(repo.path(), "packages/api/lib.rs");
+
+    check(repo.path(), "wip: a subject longer than twenty")
+        .failure()
+        .stderr(predicate::str::contains("subject exceeds 20 characters"));
+    // Root config still applies underneath
+    check(repo.path(), "wip: short").success();
+}
+
+#[test]
+fn package_config_ignored_for_commits_spanning_packages() {
+    let repo = create_monorepo();
+    stage(repo.path(), "packages/api/lib.rs");
+    stage(repo.path(), "packages/web/index.ts");
+
+    check(repo.path(), "wip: a subject longer than twenty").success();
+}
+
+#[test]
+fn config_show_lists_package_config_origin() {
+    let repo = create_monorepo();
+    stage(repo.path(), "packages/api/lib.rs");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["config", "show"])
+        .current_dir(repo.path())
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::is_match(r"max_subject = 20  # .*api[/\\]\.cc-check\.toml").unwrap(),
+        );
+}
+
+#[test]
+fn range_mode_ignores_staged_paths() {
+    let repo = create_monorepo();
+    commit(repo.path(), "chore: initial commit");
+    git(repo.path(), &["tag", "base"]);
+    commit(repo.path(), "wip: a subject longer than twenty");
+    stage(repo.path(), "packages/api/lib.rs");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--range", "base..HEAD"])
+        .current_dir(repo.path())
+        .assert()
+        .success();
+}
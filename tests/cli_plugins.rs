@@ -0,0 +1,90 @@
+#![cfg(unix)]
+
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+/// Stub plugin: fix commits must explain themselves in a body, and "tmp" scopes get a warning
+const STUB: &str = r#"#!/bin/sh
+input=$(cat)
+case "$input" in
+  *'"type":"fix"'*'"body":""'*)
+    echo '{"protocol":1,"diagnostics":[{"rule":"explain-fix","message":"fix commits need a body","target":"body"}]}' ;;
+  *'"scope":"tmp"'*)
+    echo '{"protocol":1,"diagnostics":[{"message":"temporary scope","severity":"warning","target":"scope"}]}' ;;
+  *) echo '{"protocol":1,"diagnostics":[]}' ;;
+esac
+"#;
+
+/// Create a directory with the stub plugin at `.cc-check/plugins/stub.sh`
+fn create_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::create_dir(temp_dir.path().join(".git")).unwrap();
+    let plugins = temp_dir.path().join(".cc-check").join("plugins");
+    std::fs::create_dir_all(&plugins).unwrap();
+    let script = plugins.join("stub.sh");
+    std::fs::write(&script, STUB).unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        "[[plugins]]\nid = \"stub\"\ncommand = \".cc-check/plugins/stub.sh\"\n",
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str, format: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--format", format])
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+#[test]
+fn plugin_diagnostics_are_reported() {
+    let repo = create_repo();
+
+    check(
+        repo.path(),
+        "fix: crash\n\nThe cache was not reset.\n",
+        "text",
+    )
+    .success();
+    check(repo.path(), "fix: crash\n", "text")
+        .failure()
+        .stderr(predicate::str::contains(
+            "fix commits need a body [stub/explain-fix]",
+        ));
+    check(repo.path(), "feat(tmp): try x\n", "text")
+        .success()
+        .stderr(predicate::str::contains("warning: temporary scope [stub]"));
+    check(repo.path(), "fix: crash\n", "json")
+        .failure()
+        .stdout(predicate::str::contains(
+            r#""violations":[{"rule":"stub/explain-fix","message":"fix commits need a body","severity":"error","target":"body"}]"#,
+        ));
+}
+
+#[test]
+fn plugin_timeout_fails_the_check() {
+    let repo = create_repo();
+    std::fs::write(
+        repo.path().join(".cc-check.toml"),
+        "[[plugins]]\nid = \"slow\"\ncommand = \"sh\"\nargs = [\"-c\", \"sleep 5\"]\ntimeout_ms = 100\n",
+    )
+    .unwrap();
+
+    check(repo.path(), "feat: add x\n", "text")
+        .failure()
+        .stderr(predicate::str::contains(
+            "plugin `slow` failed: timed out after 100ms",
+        ));
+}
@@ -0,0 +1,103 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::write_temp;
+
+#[test]
+fn preset_flag_switches_convention() {
+    let file = write_temp("[BUGFIX beta] Fix observer teardown");
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--preset", "ember"])
+        .arg(file.path())
+        .assert()
+        .success();
+
+    let file = write_temp("fix: observer teardown");
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--preset", "ember"])
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("header must match"));
+}
+
+#[test]
+fn angular_preset_rejects_capitalized_subject() {
+    let file = write_temp("feat: Add thing");
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--preset", "angular"])
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "subject must not start with an uppercase letter",
+        ));
+}
+
+#[test]
+fn eslint_preset_restricts_types() {
+    let file = write_temp("Fix: Semi-colon insertion");
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--preset", "eslint"])
+        .arg(file.path())
+        .assert()
+        .success();
+
+    let file = write_temp("Feature: Semi-colon insertion");
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--preset", "eslint"])
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("type 'Feature' is not allowed"));
+}
+
+#[test]
+fn preset_from_config_file_can_be_customized() {
+    let temp_dir = TempDir::new().unwrap();
+    std::fs::write(
+        temp_dir.path().join("Cargo.toml"),
+        "[package]\nname = \"test\"",
+    )
+    .unwrap();
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        "preset = \"jquery\"\nmax_subject = 20\n",
+    )
+    .unwrap();
+
+    let msg_file = temp_dir.path().join("commit_msg.txt");
+    std::fs::write(&msg_file, "Ajax: Fix timeout handling").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_dir.path())
+        .assert()
+        .success();
+
+    std::fs::write(&msg_file, "Ajax: Fix timeout handling for slow servers").unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(temp_dir.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("subject exceeds 20 characters"));
+}
+
+#[test]
+fn unknown_preset_lists_available_presets() {
+    let file = write_temp("feat: add thing");
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--preset", "gitmoji"])
+        .arg(file.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("unknown preset `gitmoji`"))
+        .stderr(predicate::str::contains("angular, conventional"));
+}
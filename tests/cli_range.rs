@@ -0,0 +1,231 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+
+mod common;
+use common::{commit, git, init_repo_with_base};
+
+fn check_range(repo: &Path) -> Command {
+    let mut cmd = Command::new(cargo_bin!("cc-check"));
+    cmd.args(["check", "--range", "base..HEAD"])
+        .current_dir(repo);
+    cmd
+}
+
+#[test]
+fn range_passes_when_every_commit_is_valid() {
+    let repo = init_repo_with_base();
+    commit(repo.path(), "feat: add feature");
+    commit(repo.path(), "fix: fix bug");
+
+    check_range(repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Checked 2 commits: 0 failed, 0 skipped",
+        ));
+}
+
+#[test]
+fn range_reports_invalid_commits() {
+    let repo = init_repo_with_base();
+    commit(repo.path(), "feat: add feature");
+    commit(repo.path(), "bad commit message");
+
+    check_range(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "bad commit message: header must match",
+        ))
+        .stdout(predicate::str::contains("1 failed"));
+}
+
+#[test]
+fn skipped_commit_is_recorded_and_listed() {
+    let repo = init_repo_with_base();
+    let msg_file = repo.path().join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, "hotfix the outage\n").unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .env("CC_CHECK_SKIP", "1")
+        .current_dir(repo.path())
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "validation skipped (CC_CHECK_SKIP)",
+        ));
+    assert_eq!(
+        std::fs::read_to_string(&msg_file).unwrap(),
+        "hotfix the outage\n\nCc-Check-Skipped: CC_CHECK_SKIP\n"
+    );
+
+    git(
+        repo.path(),
+        &["commit", "-q", "--allow-empty", "-F", ".git/COMMIT_EDITMSG"],
+    );
+    commit(repo.path(), "urgent rollback [skip cc-check]");
+    commit(repo.path(), "feat: add feature");
+
+    check_range(repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Skipped commits (2):"))
+        .stdout(predicate::str::contains(
+            "hotfix the outage (CC_CHECK_SKIP)",
+        ))
+        .stdout(predicate::str::contains(
+            "urgent rollback [skip cc-check] ([skip cc-check])",
+        ));
+}
+
+#[test]
+fn skip_trailer_only_counts_in_the_footer_block() {
+    let repo = init_repo_with_base();
+    commit(
+        repo.path(),
+        "hotfix the outage\n\nCc-Check-Skipped: CC_CHECK_SKIP\nwas copied from another commit",
+    );
+
+    check_range(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "hotfix the outage: header must match",
+        ))
+        .stdout(predicate::str::contains("0 skipped"));
+}
+
+#[test]
+fn skip_is_recorded_after_body_ending_in_footer_like_line() {
+    let repo = init_repo_with_base();
+    let msg_file = repo.path().join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(
+        &msg_file,
+        "hotfix the outage\n\nSee the docs\nNote: this is tricky\n",
+    )
+    .unwrap();
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .env("CC_CHECK_SKIP", "1")
+        .current_dir(repo.path())
+        .assert()
+        .success();
+    git(
+        repo.path(),
+        &["commit", "-q", "--allow-empty", "-F", ".git/COMMIT_EDITMSG"],
+    );
+
+    check_range(repo.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Checked 1 commits: 0 failed, 1 skipped",
+        ));
+}
+
+#[test]
+fn hand_written_skip_trailer_needs_a_known_reason() {
+    let repo = init_repo_with_base();
+    commit(
+        repo.path(),
+        "hotfix the outage\n\nCc-Check-Skipped: trust me",
+    );
+
+    check_range(repo.path())
+        .assert()
+        .failure()
+        .stdout(predicate::str::contains("0 skipped"));
+}
+
+#[test]
+fn skipping_can_be_disabled() {
+    let repo = init_repo_with_base();
+    std::fs::write(repo.path().join(".cc-check.toml"), "allow_skip = false\n").unwrap();
+    commit(repo.path(), "urgent rollback [skip cc-check]");
+
+    check_range(repo.path()).assert().failure();
+
+    let output = check_range(repo.path())
+        .args(["--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["ok"], false);
+    assert_eq!(
+        json["commits"][0]["header"],
+        "urgent rollback [skip cc-check]"
+    );
+}
+
+#[test]
+fn unsquashed_fixups_can_be_rejected_in_range_mode() {
+    let repo = init_repo_with_base();
+    commit(repo.path(), "feat: add feature");
+    commit(repo.path(), "fixup! feat: add feature");
+
+    check_range(repo.path()).assert().success();
+
+    check_range(repo.path())
+        .args(["--forbid-unsquashed", "true"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "fixup! feat: add feature: unsquashed fixup! commit; run `git rebase -i --autosquash` before merging",
+        ));
+}
+
+fn head_sha(repo: &Path) -> String {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(repo)
+        .output()
+        .unwrap();
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn revert_refs_are_verified_in_range_mode() {
+    let repo = init_repo_with_base();
+    std::fs::write(
+        repo.path().join(".cc-check.toml"),
+        "require_revert_refs = true\n",
+    )
+    .unwrap();
+    commit(repo.path(), "feat: add feature");
+    let sha = head_sha(repo.path());
+    git(repo.path(), &["tag", "feature"]);
+
+    commit(
+        repo.path(),
+        &format!("revert: feat: add feature\n\nRefs: {}", &sha[..10]),
+    );
+    check_range(repo.path()).assert().success();
+
+    commit(repo.path(), "revert: add feature\n\nRefs: 0123456789abcdef");
+    check_range(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "reverted commit 0123456789abcdef not found in history",
+        ));
+
+    git(repo.path(), &["reset", "-q", "--hard", "feature"]);
+    commit(
+        repo.path(),
+        &format!("revert: something else\n\nRefs: {sha}"),
+    );
+    check_range(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "revert subject does not match the header of reverted commit",
+        ));
+}
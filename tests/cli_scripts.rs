@@ -0,0 +1,84 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::{commit, init_repo_with_base};
+
+/// Create a git repository with the given `.cc-check/rules/*.rhai` scripts and an
+/// initial commit
+fn create_repo(scripts: &[(&str, &str)]) -> TempDir {
+    let temp_dir = init_repo_with_base();
+    let rules = temp_dir.path().join(".cc-check").join("rules");
+    std::fs::create_dir_all(&rules).unwrap();
+    for (name, source) in scripts {
+        std::fs::write(rules.join(name), source).unwrap();
+    }
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str, format: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--format", format])
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+const FIX_BODY: &str = r#"
+if commit.type == "fix" && commit.body == "" {
+    error("fix commits need a body", "body");
+}
+if commit.scope == "tmp" {
+    warning("temporary scope", "scope");
+}
+"#;
+
+#[test]
+fn scripts_report_in_text_and_json() {
+    let repo = create_repo(&[("fix-body.rhai", FIX_BODY)]);
+
+    check(repo.path(), "fix: crash\n\nReset the cache.\n", "text").success();
+    check(repo.path(), "fix: crash\n", "text")
+        .failure()
+        .stderr(predicate::str::contains(
+            "fix commits need a body [fix-body]",
+        ));
+    check(repo.path(), "feat(tmp): try x\n", "json")
+        .success()
+        .stdout(predicate::str::contains(
+            r#""violations":[{"rule":"fix-body","message":"temporary scope","severity":"warning","target":"scope"}]"#,
+        ));
+}
+
+#[test]
+fn scripts_run_for_every_commit_in_a_range() {
+    let repo = create_repo(&[("fix-body.rhai", FIX_BODY)]);
+    commit(repo.path(), "fix: crash");
+    commit(repo.path(), "fix: leak\n\nFree the buffer.");
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--range", "base..HEAD"])
+        .current_dir(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "fix: crash: fix commits need a body [fix-body]",
+        ))
+        .stdout(predicate::str::contains("Checked 2 commits: 1 failed"));
+}
+
+#[test]
+fn script_errors_fail_the_check() {
+    let repo = create_repo(&[("broken.rhai", "let x = commit.nope.length;")]);
+
+    check(repo.path(), "feat: add x\n", "text")
+        .failure()
+        .stderr(predicate::str::contains("rule script `broken` failed"));
+}
@@ -0,0 +1,146 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::{commit, git, init_repo};
+
+/// Create a git repository for "Test <test@example.com>" with the given `[signoff]` table
+fn create_repo(signoff: &str) -> TempDir {
+    let temp_dir = init_repo();
+    git(temp_dir.path(), &["config", "user.name", "Test"]);
+    git(
+        temp_dir.path(),
+        &["config", "user.email", "test@example.com"],
+    );
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        format!("[signoff]\n{signoff}"),
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str, args: &[&str]) -> Command {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    let mut cmd = Command::new(cargo_bin!("cc-check"));
+    cmd.arg("check").args(args).arg(&msg_file).current_dir(repo);
+    cmd
+}
+
+#[test]
+fn missing_signoff_fails() {
+    let repo = create_repo("required = true\n");
+
+    check(repo.path(), "feat: add x\n", &[])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "missing `Signed-off-by: Name <email>` trailer",
+        ));
+    check(
+        repo.path(),
+        "feat: add x\n\nSigned-off-by: Someone <someone@example.com>\n",
+        &[],
+    )
+    .assert()
+    .success();
+}
+
+#[test]
+fn fix_appends_signoff_from_git_identity() {
+    let repo = create_repo("required = true\nmatch_author = true\n");
+
+    check(
+        repo.path(),
+        "feat: add x\n\nRefs: #1\n# Please enter the commit message for your changes.\n",
+        &["--fix"],
+    )
+    .assert()
+    .success()
+    .stderr(predicate::str::contains(
+        "cc-check: added `Signed-off-by: Test <test@example.com>`",
+    ));
+
+    let fixed = std::fs::read_to_string(repo.path().join(".git").join("COMMIT_EDITMSG")).unwrap();
+    assert_eq!(
+        fixed,
+        "feat: add x\n\nRefs: #1\nSigned-off-by: Test <test@example.com>\n# Please enter the commit message for your changes.\n"
+    );
+}
+
+#[test]
+fn fix_starts_new_paragraph_after_footer_like_body_line() {
+    let repo = create_repo("required = true\n");
+
+    check(
+        repo.path(),
+        "feat: add x\n\nSee the docs\nNote: this is tricky\n",
+        &["--fix"],
+    )
+    .assert()
+    .success();
+
+    let fixed = std::fs::read_to_string(repo.path().join(".git").join("COMMIT_EDITMSG")).unwrap();
+    assert_eq!(
+        fixed,
+        "feat: add x\n\nSee the docs\nNote: this is tricky\n\nSigned-off-by: Test <test@example.com>\n"
+    );
+}
+
+#[test]
+fn signoff_must_match_hook_author() {
+    let repo = create_repo("required = true\nmatch_author = true\n");
+
+    check(
+        repo.path(),
+        "feat: add x\n\nSigned-off-by: Test <test@example.com>\n",
+        &[],
+    )
+    .env("GIT_AUTHOR_NAME", "Other")
+    .env("GIT_AUTHOR_EMAIL", "other@example.com")
+    .assert()
+    .failure()
+    .stderr(predicate::str::contains(
+        "commit must be signed off by its author Other <other@example.com>",
+    ));
+}
+
+#[test]
+fn range_matches_each_commit_author() {
+    let repo = create_repo("required = true\nmatch_author = true\n");
+    commit(repo.path(), "chore: initial commit");
+    git(repo.path(), &["tag", "base"]);
+    git(
+        repo.path(),
+        &["commit", "-q", "--allow-empty", "-s", "-m", "feat: signed"],
+    );
+    git(
+        repo.path(),
+        &[
+            "commit",
+            "-q",
+            "--allow-empty",
+            "-s",
+            "--author",
+            "Other <other@example.com>",
+            "-m",
+            "fix: signed by the committer only",
+        ],
+    );
+
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check", "--range", "base..HEAD"])
+        .current_dir(repo.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "fix: signed by the committer only: commit must be signed off by its author Other <other@example.com>",
+        ))
+        .stdout(predicate::str::contains("Checked 2 commits: 1 failed"));
+}
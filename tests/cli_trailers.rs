@@ -0,0 +1,112 @@
+use assert_cmd::prelude::*;
+#[macro_use]
+extern crate assert_cmd;
+use predicates::prelude::*;
+use std::path::Path;
+use std::process::Command;
+use tempfile::TempDir;
+
+mod common;
+use common::init_repo;
+
+/// Create a git repository with the given `[trailers]` table
+fn create_repo(trailers: &str) -> TempDir {
+    let temp_dir = init_repo();
+    std::fs::write(
+        temp_dir.path().join(".cc-check.toml"),
+        format!("[trailers]\n{trailers}"),
+    )
+    .unwrap();
+    temp_dir
+}
+
+fn check(repo: &Path, message: &str) -> assert_cmd::assert::Assert {
+    let msg_file = repo.join(".git").join("COMMIT_EDITMSG");
+    std::fs::write(&msg_file, message).unwrap();
+    Command::new(cargo_bin!("cc-check"))
+        .args(["check"])
+        .arg(&msg_file)
+        .current_dir(repo)
+        .assert()
+}
+
+#[test]
+fn co_author_needs_valid_email() {
+    let repo = create_repo("");
+
+    check(
+        repo.path(),
+        "feat: pair on x\n\nCo-authored-by: Jane Doe <jane@example.com>\n",
+    )
+    .success();
+    check(
+        repo.path(),
+        "feat: pair on x\n\nCo-authored-by: Jane Doe <jane@localhost>\n",
+    )
+    .failure()
+    .stderr(predicate::str::contains(
+        "invalid `Co-authored-by` trailer `Jane Doe <jane@localhost>` (expected `Name <email>`)",
+    ));
+}
+
+#[test]
+fn allowed_required_order_and_duplicates() {
+    let repo = create_repo(
+        r#"allowed = ["Refs", "Reviewed-by", "Co-authored-by"]
+required = ["Reviewed-by"]
+order = ["Refs", "Reviewed-by"]
+duplicates = "forbid-identical"
+"#,
+    );
+
+    check(
+        repo.path(),
+        "fix: y\n\nRefs: #1\nReviewed-by: Jane <jane@example.com>\n",
+    )
+    .success();
+    check(repo.path(), "fix: y\n\nRefs: #1\n")
+        .failure()
+        .stderr(predicate::str::contains(
+            "missing required `Reviewed-by:` trailer",
+        ));
+    check(repo.path(), "fix: y\n\nReviewed-by: Jane\nAcked-by: Joe\n")
+        .failure()
+        .stderr(predicate::str::contains(
+            "trailer `Acked-by` is not in trailers.allowed",
+        ));
+    check(repo.path(), "fix: y\n\nReviewed-by: Jane\nRefs: #1\n")
+        .failure()
+        .stderr(predicate::str::contains(
+            "`Refs` trailers must come before `Reviewed-by` trailers",
+        ));
+    check(
+        repo.path(),
+        "fix: y\n\nReviewed-by: Jane\nReviewed-by: Jane\n",
+    )
+    .failure()
+    .stderr(predicate::str::contains("duplicate `Reviewed-by` trailer"));
+}
+
+#[test]
+fn co_authors_checked_against_mailmap() {
+    let repo = create_repo("mailmap = true\n");
+    std::fs::write(
+        repo.path().join(".mailmap"),
+        "Jane Doe <jane@example.com> <jdoe@old.example.com>\n",
+    )
+    .unwrap();
+
+    check(
+        repo.path(),
+        "feat: pair on x\n\nCo-authored-by: Jane <jdoe@old.example.com>\n",
+    )
+    .success();
+    check(
+        repo.path(),
+        "feat: pair on x\n\nCo-authored-by: Eve <eve@example.com>\n",
+    )
+    .failure()
+    .stderr(predicate::str::contains(
+        "co-author Eve <eve@example.com> is not listed in .mailmap",
+    ));
+}
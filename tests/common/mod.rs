@@ -1,5 +1,10 @@
+// Each test binary compiles this module and uses only some of the helpers
+#![allow(dead_code)]
+
 use std::io::Write;
-use tempfile::NamedTempFile;
+use std::path::Path;
+use std::process::Command;
+use tempfile::{NamedTempFile, TempDir};
 
 /// Helper function to create a temporary file with content for testing
 pub fn write_temp(contents: &str) -> NamedTempFile {
@@ -7,3 +12,35 @@ pub fn write_temp(contents: &str) -> NamedTempFile {
     write!(file, "{}", contents).expect("write temp file contents");
     file
 }
+
+/// Run git in `dir` with a fixed identity, failing the test if it fails
+pub fn git(dir: &Path, args: &[&str]) {
+    let status = Command::new("git")
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?} failed", args);
+}
+
+/// Create an empty git repository
+pub fn init_repo() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    git(temp_dir.path(), &["init", "-q"]);
+    temp_dir
+}
+
+/// Create a git repository with an initial commit tagged `base`, on which ranges such
+/// as `base..HEAD` can be based
+pub fn init_repo_with_base() -> TempDir {
+    let temp_dir = init_repo();
+    commit(temp_dir.path(), "chore: initial commit");
+    git(temp_dir.path(), &["tag", "base"]);
+    temp_dir
+}
+
+/// Record an empty commit with `message`
+pub fn commit(repo: &Path, message: &str) {
+    git(repo, &["commit", "-q", "--allow-empty", "-m", message]);
+}